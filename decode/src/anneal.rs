@@ -0,0 +1,193 @@
+/*
+    Simulated annealing for a repeating key. The hill climb in Ngram::generate_key_from_parent only
+    takes changes that score better, so it stops at the first key no single letter change improves.
+    When the chi-squared key it starts from is wrong in several columns (short texts, or text
+    with odd letter frequencies) that is often not the real key. Annealing takes worse changes
+    too, with probability exp(delta / T), so it can climb back out of such a key. Here delta is
+    how much the score dropped for each letter of the changed column, which keeps T meaning
+    the same whatever the length of the text and the key. The temperature T falls from start to end
+    over the run following the schedule, so early on it wanders freely and by the end only
+    improvements get through. The best key seen at any point is the one returned.
+
+    https://en.wikipedia.org/wiki/Simulated_annealing
+    http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-playfair/
+*/
+use std::fmt;
+use std::str::FromStr;
+
+use decode_given_key::{decode_with_tableau, to_letter_indices, Ngram};
+use decode_given_length::find_key_with_shifts;
+use keyphrase::{group_ciphertext, Alphabet, KeyPhrase, Tableau, VigenereError, ALPHABET_LEN};
+
+use crate::{determine_key_length_in, DEFAULT_SEED};
+use crate::random::Xorshift;
+
+pub const DEFAULT_START_TEMPERATURE: f64 = 4.0;
+pub const DEFAULT_END_TEMPERATURE: f64 = 0.04;
+pub const DEFAULT_ITERATIONS: usize = 20_000;
+
+// how the temperature gets from start to end over the iterations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Schedule {
+    Linear, // falls by the same amount every iteration
+    #[default]
+    Geometric, // falls by the same factor every iteration, so more of the run is spent cool
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 2] = [Schedule::Linear, Schedule::Geometric];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schedule::Linear => "linear",
+            Schedule::Geometric => "geometric",
+        }
+    }
+
+    // the temperature after the fraction progress (0.0 to 1.0) of the run
+    pub fn temperature(&self, start: f64, end: f64, progress: f64) -> f64 {
+        match self {
+            Schedule::Linear => start + (end - start) * progress,
+            Schedule::Geometric => start * (end / start).powf(progress),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Schedule, String> {
+        match Schedule::ALL.iter().find(|schedule| schedule.name() == s.to_ascii_lowercase()) {
+            Some(schedule) => Ok(*schedule),
+            None => Err(format!("unknown schedule {}, expected linear or geometric", s)),
+        }
+    }
+}
+
+// everything that decides how an annealing run goes, the same settings and seed always give the same key
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annealing {
+    pub schedule: Schedule,
+    pub start_temperature: f64,
+    pub end_temperature: f64, // must be above 0 for Schedule::Geometric
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for Annealing {
+    fn default() -> Annealing {
+        Annealing {
+            schedule: Schedule::default(),
+            start_temperature: DEFAULT_START_TEMPERATURE,
+            end_temperature: DEFAULT_END_TEMPERATURE,
+            iterations: DEFAULT_ITERATIONS,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnealSolution {
+    pub key: String,
+    pub score: f64, // Ngram::score_letters of the plaintext's letters
+    pub plaintext: String,
+}
+
+// cracks with the key length from determine_key_length_in, or key_length when given, starting from the chi-squared key
+pub fn crack_annealed(ciphertext: &str, ngram: &Ngram, tableau: Tableau, key_length: Option<usize>, annealing: &Annealing) -> Result<AnnealSolution, VigenereError> {
+    let buckets = match key_length {
+        Some(0) => return Err(VigenereError::ZeroKeyLength),
+        Some(key_length) => group_ciphertext(&ciphertext.to_ascii_uppercase(), key_length),
+        None => determine_key_length_in(ciphertext, &Alphabet::letters(), ngram.language())?.1,
+    };
+    let letters = to_letter_indices(ciphertext);
+    if letters.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: ngram.ngram_length() });
+    }
+
+    let parent = to_letter_indices(&find_key_with_shifts(&buckets, tableau, ALPHABET_LEN as u8, ngram.language()));
+    let (key, score) = anneal_key(&letters, &parent, ngram, tableau, annealing);
+    let mut keyphrase = KeyPhrase::new(key.clone())?;
+    Ok(AnnealSolution { plaintext: decode_with_tableau(ciphertext, &mut keyphrase, tableau)?, key, score })
+}
+
+// anneals from parent (shifts 0-25) over the ciphertext's letters, returning the best key seen and its score
+pub fn anneal_key(ciphertext: &[u8], parent: &[u8], ngram: &Ngram, tableau: Tableau, annealing: &Annealing) -> (String, f64) {
+    let mut rng = Xorshift::new(annealing.seed);
+    let mut key = parent.to_vec();
+    let mut plaintext = vec![0; ciphertext.len()];
+    let mut score = ngram.score_key(&key, ciphertext, &mut plaintext, tableau);
+    let mut best = (key.clone(), score);
+    // letters in a column, so a change is judged by what it does to each letter it touches
+    let column_letters = f64::max(ciphertext.len() as f64 / key.len() as f64, 1.0);
+
+    for iteration in 0..annealing.iterations {
+        let progress = iteration as f64 / annealing.iterations as f64;
+        let temperature = annealing.schedule.temperature(annealing.start_temperature, annealing.end_temperature, progress);
+
+        let i = rng.below(key.len());
+        let old = key[i];
+        // any letter but the current one
+        key[i] = ((old as usize + 1 + rng.below(ALPHABET_LEN - 1)) % ALPHABET_LEN) as u8;
+        let candidate = ngram.score_key(&key, ciphertext, &mut plaintext, tableau);
+        let delta = (candidate - score) / column_letters;
+        if delta >= 0.0 || (temperature > 0.0 && rng.unit() < (delta / temperature).exp()) {
+            score = candidate;
+            if score > best.1 {
+                best = (key.clone(), score);
+            }
+        }
+        else {
+            key[i] = old;
+        }
+    }
+    (best.0.iter().map(|&shift| (shift + b'A') as char).collect(), best.1)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decode_given_length::{SECRZUTY_CIPHERTEXT, SECRZUTY_PLAINTEXT};
+
+    #[test]
+    fn anneals_past_hill_climb() {
+        let solution = crack_annealed(SECRZUTY_CIPHERTEXT, &Ngram::english(), Tableau::Vigenere, Some(8), &Annealing::default()).unwrap();
+        assert_eq!((solution.key.as_str(), solution.plaintext.as_str()), ("SECURITY", SECRZUTY_PLAINTEXT));
+        assert_eq!(solution.score, Ngram::english().compute_score(SECRZUTY_PLAINTEXT));
+    }
+
+    #[test]
+    fn schedules() {
+        assert_eq!(Schedule::Linear.temperature(4.0, 0.0, 0.25), 3.0);
+        assert!((Schedule::Geometric.temperature(4.0, 0.04, 0.5) - 0.4).abs() < 1e-9);
+        assert_eq!("Linear".parse(), Ok(Schedule::Linear));
+        assert!("cubic".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn anneals_skewed_text() {
+        // the start of the lipogram in decode's paragraph_no_e1 test: with no E the chi-squared key is
+        // SRNBRITY, wrong in three columns, and the hill climb from it only puts one of them right
+        let plaintext = "If youth, throughout all history, had had a champion to stand up for it;";
+        let ciphertext = encode::encode(plaintext, &mut KeyPhrase::new(String::from("SECURITY")).unwrap());
+        let buckets = group_ciphertext(&ciphertext.to_ascii_uppercase(), 8);
+        assert_eq!(decode_given_length::decode_given_length(&ciphertext, 8, buckets).unwrap().0, "SRNURITY");
+
+        let ngram = Ngram::english();
+        let solution = crack_annealed(&ciphertext, &ngram, Tableau::Vigenere, Some(8), &Annealing::default()).unwrap();
+        assert_eq!((solution.key.as_str(), solution.plaintext.as_str()), ("SECURITY", plaintext));
+        // the same seed anneals the same way every run, and other seeds get there too
+        assert_eq!(crack_annealed(&ciphertext, &ngram, Tableau::Vigenere, Some(8), &Annealing::default()).unwrap(), solution);
+        for seed in [1, 2, 3] {
+            let annealing = Annealing { seed, ..Annealing::default() };
+            assert_eq!(crack_annealed(&ciphertext, &ngram, Tableau::Vigenere, Some(8), &annealing).unwrap().key, "SECURITY");
+        }
+    }
+}
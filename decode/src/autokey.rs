@@ -0,0 +1,122 @@
+/*
+    Cracking autokey ciphertext. The key never repeats, so there are no columns with a single
+    alphabet for the index of coincidence to find and determine_key_length is no help. What does
+    hold is that with a primer of length L, ciphertext letter i only depends on primer letter
+    i % L: that letter decrypts position i, whose plaintext decrypts position i + L, and so on.
+    So for each primer length the primer is seeded one column at a time with chi-squared, the same
+    way find_key does for a repeating key, then refined letter by letter with the n-gram model,
+    and the primer length whose plaintext scores best wins.
+
+    http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-autokey-cipher/
+*/
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use decode_given_key::{decode_with_tableau, to_letter_indices, Ngram};
+use keyphrase::{calculate_chi_squared_for, AutoKey, Language, Tableau, VigenereError};
+
+pub const DEFAULT_PRIMER_LENGTHS: RangeInclusive<usize> = 1..=20;
+
+// finds the primer and plaintext, trying every primer length in the range
+pub fn crack_autokey(ciphertext: &str, ngram: &Ngram, tableau: Tableau, primer_lengths: RangeInclusive<usize>) -> Result<(String, String), VigenereError> {
+    let letters = to_letter_indices(ciphertext);
+    if letters.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: ngram.ngram_length() });
+    }
+    let shortest = usize::max(*primer_lengths.start(), 1);
+    let longest = usize::min(*primer_lengths.end(), letters.len());
+    if shortest > longest {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: shortest });
+    }
+
+    let mut plaintext = vec![0; letters.len()];
+    let mut best: Option<(f64, Vec<u8>)> = None;
+    // shortest first, a longer primer has to score strictly better to replace it
+    for length in shortest..=longest {
+        let primer = climb_primer(seed_primer(&letters, length, tableau, ngram.language()), &letters, ngram, tableau, &mut plaintext);
+        decrypt(&letters, &primer, tableau, &mut plaintext);
+        let score = ngram.score_letters(&plaintext);
+        if best.as_ref().map_or(true, |(best_score, _)| score > *best_score) {
+            best = Some((score, primer));
+        }
+    }
+
+    let primer: String = best.unwrap().1.iter().map(|&l| (l + b'A') as char).collect();
+    let mut autokey = AutoKey::new(primer.clone())?;
+    Ok((primer, decode_with_tableau(ciphertext, &mut autokey, tableau)?))
+}
+
+// letters of ciphertext decrypted with an autokey primer, all as 0..=25
+fn decrypt(ciphertext: &[u8], primer: &[u8], tableau: Tableau, plaintext: &mut [u8]) {
+    for i in 0..ciphertext.len() {
+        let key = if i < primer.len() { primer[i] } else { plaintext[i - primer.len()] };
+        plaintext[i] = tableau.decrypt(ciphertext[i], key);
+    }
+}
+
+// http://practicalcryptography.com/cryptanalysis/text-characterisation/chi-squared-statistic/
+// picks each primer letter on its own from how much its column of the plaintext looks like language
+fn seed_primer(ciphertext: &[u8], length: usize, tableau: Tableau, language: Language) -> Vec<u8> {
+    (0..length)
+        .map(|column| {
+            let mut min: f64 = f64::MAX;
+            let mut best = 0;
+            for candidate in 0..26 {
+                let mut counts: HashMap<char, usize> = HashMap::new();
+                let mut key = candidate;
+                for &c in ciphertext.iter().skip(column).step_by(length) {
+                    key = tableau.decrypt(c, key);
+                    *counts.entry((key + b'A') as char).or_insert(0) += 1;
+                }
+                let chi_sq = calculate_chi_squared_for(&counts, counts.values().sum(), language);
+                if chi_sq < min {
+                    min = chi_sq;
+                    best = candidate;
+                }
+            }
+            best
+        })
+        .collect()
+}
+
+// same greedy climb as Ngram::generate_key_from_parent, changing one primer letter at a time
+fn climb_primer(mut primer: Vec<u8>, ciphertext: &[u8], ngram: &Ngram, tableau: Tableau, plaintext: &mut [u8]) -> Vec<u8> {
+    decrypt(ciphertext, &primer, tableau, plaintext);
+    let mut best_score = ngram.score_letters(plaintext);
+    loop {
+        let before = primer.clone();
+        for i in 0..primer.len() {
+            let mut best_letter = primer[i];
+            for letter in 0..26 {
+                primer[i] = letter;
+                decrypt(ciphertext, &primer, tableau, plaintext);
+                let score = ngram.score_letters(plaintext);
+                if score > best_score {
+                    best_letter = letter;
+                    best_score = score;
+                }
+            }
+            primer[i] = best_letter;
+        }
+        if primer == before {
+            return primer;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cracks_autokey() {
+        // the ranked_decode plaintext encrypted with autokey, primer FORTIFY
+        let ciphertext = "YVVKMFPXAAFAAPWHBQKNQLFZEHVFZRMQYBJGRWRJLEGESQIOIEVGGSIAIMAMGASSQXEWHZIFISIKLAKXVFMMOLWZZIWRYXTAVSQGNMUIRQVPIGTUHKDEMBZXFIAIMAMGACYQXEAQCHQSEPCTMLHKLAKXUSFFVZSHGRFAQQCWQGNMUBLRHQVKMTIYPFVBEJTYARZWIIWRWTYOB";
+        let (primer, plaintext) = crack_autokey(ciphertext, &Ngram::english(), Tableau::Vigenere, DEFAULT_PRIMER_LENGTHS).unwrap();
+        assert_eq!(primer, "FORTIFY");
+        assert!(plaintext.starts_with("THEREARETWOWAYSOFCONSTRUCTINGASOFTWAREDESIGN"));
+
+        assert!(matches!(crack_autokey("ABC", &Ngram::english(), Tableau::Vigenere, DEFAULT_PRIMER_LENGTHS), Err(VigenereError::CiphertextTooShort { .. })));
+    }
+}
@@ -0,0 +1,184 @@
+/*
+    Known plaintext attack. When a word or phrase in the message is known (a salutation, a name,
+    ATTACK AT DAWN) but not where it is, the crib is tried at every position of the ciphertext's
+    letters. Each one implies the key letters under it, and at the right position those repeat
+    with the period of the key, which a wrong position only does by chance. So a position counts
+    when its key fragment is periodic with a length in the range, checked by at least
+    MIN_CONFIRMING_LETTERS letters, and the period's worth of fragment is the whole key. The
+    crib therefore has to be that many letters longer than the key. Every such key decrypts the
+    whole ciphertext and the n-gram model ranks the results.
+
+    Porta pairs up key letters, so a fragment holds the first of each pair (A for AB), which
+    decrypts the same. Its alphabets never map a letter into its own half, which rules out
+    positions without any key at all.
+
+    drag_crib is the same by hand: with the key length known or guessed, it lines the crib up
+    at every position and shows which key letters that gives in which columns, and what the
+    rest of the ciphertext decrypts to with only those, for an analyst to judge.
+
+    https://en.wikipedia.org/wiki/Known-plaintext_attack
+*/
+use std::ops::RangeInclusive;
+
+use decode_given_key::{decode_partial, decode_with_tableau, to_letter_indices, Ngram};
+use keyphrase::{KeyPhrase, PartialKey, Tableau, VigenereError, ALPHABET_LEN};
+
+pub const MIN_CONFIRMING_LETTERS: usize = 3; // a wrong position gives a period this long by chance one time in 26^3
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribMatch {
+    pub position: usize, // letters of the ciphertext before the crib
+    pub key: String,
+    pub score: f64, // n-gram score of the plaintext, higher is better
+    pub plaintext: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribAlignment {
+    pub position: usize,  // letters of the ciphertext before the crib
+    pub key: PartialKey,  // what the crib gives for each column, unknown where it doesn't reach or contradicts itself
+    pub conflicts: usize, // columns the crib gives two different letters for, never any at the right position
+    pub plaintext: String, // decrypted with key, see decode_given_key::decode_partial
+}
+
+// every position where the crib gives a periodic key fragment, best scoring first
+pub fn search_crib(ciphertext: &str, crib: &str, ngram: &Ngram, tableau: Tableau, lengths: RangeInclusive<usize>) -> Result<Vec<CribMatch>, VigenereError> {
+    let letters = to_letter_indices(ciphertext);
+    let crib = to_letter_indices(crib);
+    if crib.is_empty() {
+        return Err(VigenereError::EmptyCrib);
+    }
+    if letters.len() < crib.len() {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: crib.len() });
+    }
+
+    let mut matches: Vec<CribMatch> = Vec::new();
+    for position in 0..=letters.len() - crib.len() {
+        let fragment = match implied_key(&letters[position..position + crib.len()], &crib, tableau) {
+            Some(fragment) => fragment,
+            None => continue,
+        };
+        let period = match shortest_period(&fragment, &lengths) {
+            Some(period) => period,
+            None => continue,
+        };
+        // fragment[i] is the key letter for ciphertext letter position + i, so key letter j is fragment[j - position]
+        let key: String = (0..period).map(|j| (fragment[(j + period - position % period) % period] + b'A') as char).collect();
+        let mut keyphrase = KeyPhrase::new(key.clone())?;
+        let plaintext = decode_with_tableau(ciphertext, &mut keyphrase, tableau)?;
+        matches.push(CribMatch { position, score: ngram.compute_score(&plaintext), key, plaintext });
+    }
+    // stable sort, so equal scores stay earliest position first
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(matches)
+}
+
+// the crib at every position it fits for a key of key_length letters, in order of position
+pub fn drag_crib(ciphertext: &str, crib: &str, key_length: usize, tableau: Tableau) -> Result<Vec<CribAlignment>, VigenereError> {
+    let letters = to_letter_indices(ciphertext);
+    let crib = to_letter_indices(crib);
+    if key_length == 0 {
+        return Err(VigenereError::ZeroKeyLength);
+    }
+    if crib.is_empty() {
+        return Err(VigenereError::EmptyCrib);
+    }
+    if letters.len() < crib.len() {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: crib.len() });
+    }
+
+    let mut alignments: Vec<CribAlignment> = Vec::new();
+    for position in 0..=letters.len() - crib.len() {
+        let fragment = match implied_key(&letters[position..position + crib.len()], &crib, tableau) {
+            Some(fragment) => fragment,
+            None => continue,
+        };
+        let mut columns: Vec<Option<u8>> = vec![None; key_length];
+        let mut conflicting = vec![false; key_length];
+        for (i, &shift) in fragment.iter().enumerate() {
+            let column = (position + i) % key_length;
+            match columns[column] {
+                Some(known) if known != shift => conflicting[column] = true,
+                _ => columns[column] = Some(shift),
+            }
+        }
+        for (column, _) in conflicting.iter().enumerate().filter(|(_, &conflict)| conflict) {
+            columns[column] = None;
+        }
+
+        let key = PartialKey::from_shifts(columns)?;
+        let plaintext = decode_partial(ciphertext, &key, tableau);
+        alignments.push(CribAlignment { position, key, conflicts: conflicting.iter().filter(|&&conflict| conflict).count(), plaintext });
+    }
+    Ok(alignments)
+}
+
+// the key letter that enciphers each crib letter as the ciphertext letter under it, None if one has none
+pub fn implied_key(ciphertext: &[u8], crib: &[u8], tableau: Tableau) -> Option<Vec<u8>> {
+    ciphertext
+        .iter()
+        .zip(crib)
+        .map(|(&cipher, &plain)| (0..ALPHABET_LEN as u8).find(|&key| tableau.encrypt(plain, key) == cipher))
+        .collect()
+}
+
+// the shortest length in the range the fragment repeats with, enough of it left over to confirm it
+fn shortest_period(fragment: &[u8], lengths: &RangeInclusive<usize>) -> Option<usize> {
+    lengths
+        .clone()
+        .filter(|&period| period > 0 && period + MIN_CONFIRMING_LETTERS <= fragment.len())
+        .find(|&period| (period..fragment.len()).all(|i| fragment[i] == fragment[i - period]))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_LENGTH_RANGE;
+    use encode::encode_with_tableau;
+
+    const PLAINTEXT: &str = "Our agent will cross the border at midnight and wait by the old mill until the signal comes. Attack at dawn if the bridge is still standing, otherwise fall back to the river and hold.";
+
+    #[test]
+    fn finds_key_from_crib() {
+        let ngram = Ngram::english();
+        for tableau in [Tableau::Vigenere, Tableau::Beaufort, Tableau::Porta] {
+            let mut keyphrase = KeyPhrase::new(String::from("LEMON")).unwrap();
+            let ciphertext = encode_with_tableau(PLAINTEXT, &mut keyphrase, tableau).unwrap();
+            let matches = search_crib(&ciphertext, "attack at dawn", &ngram, tableau, DEFAULT_LENGTH_RANGE).unwrap();
+            // Porta's key comes back as the first letter of each pair
+            let key = if tableau == Tableau::Porta { "KEMOM" } else { "LEMON" };
+            assert_eq!((matches[0].position, matches[0].key.as_str(), matches[0].plaintext.as_str()), (74, key, PLAINTEXT));
+        }
+    }
+
+    #[test]
+    fn drags_crib() {
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        let ciphertext = encode_with_tableau(PLAINTEXT, &mut keyphrase, Tableau::Vigenere).unwrap();
+        let alignments = drag_crib(&ciphertext, "attack", 8, Tableau::Vigenere).unwrap();
+        assert_eq!(alignments.len(), 141);
+        // the crib starts in column 74 % 8 = 2 and covers six of the eight
+        let right = &alignments[74];
+        assert_eq!((right.position, right.key.to_string(), right.conflicts), (74, String::from("??CURITY"), 0));
+        assert!(right.plaintext.starts_with("__r agent __ll"));
+        assert!(right.plaintext.contains("Attack __ dawn"));
+
+        // a 12 letter crib wraps round an 8 letter key, so at the wrong positions the letters disagree
+        let alignments = drag_crib(&ciphertext, "attack at dawn", 8, Tableau::Vigenere).unwrap();
+        let consistent: Vec<usize> = alignments.iter().filter(|alignment| alignment.conflicts == 0).map(|alignment| alignment.position).collect();
+        assert_eq!(consistent, vec![74]);
+        assert_eq!(drag_crib(&ciphertext, "attack", 0, Tableau::Vigenere), Err(VigenereError::ZeroKeyLength));
+    }
+
+    #[test]
+    fn crib_too_short_or_empty() {
+        let ngram = Ngram::english();
+        let mut keyphrase = KeyPhrase::new(String::from("LEMON")).unwrap();
+        let ciphertext = encode_with_tableau(PLAINTEXT, &mut keyphrase, Tableau::Vigenere).unwrap();
+        // 6 letters can't confirm a 5 letter key
+        assert_eq!(search_crib(&ciphertext, "attack", &ngram, Tableau::Vigenere, 5..=5), Ok(Vec::new()));
+        assert_eq!(search_crib(&ciphertext, "123", &ngram, Tableau::Vigenere, DEFAULT_LENGTH_RANGE), Err(VigenereError::EmptyCrib));
+        assert!(matches!(search_crib("ABC", "attack", &ngram, Tableau::Vigenere, DEFAULT_LENGTH_RANGE), Err(VigenereError::CiphertextTooShort { .. })));
+    }
+}
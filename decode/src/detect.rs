@@ -0,0 +1,81 @@
+/*
+    Cracking without knowing the plaintext language. Every language profile cracks the ciphertext
+    in turn, with its own IOC, letter frequencies and quadgram model, and each plaintext is scored
+    by the model that produced it. Raw n-gram scores depend on how many n-grams a corpus has, so
+    Ngram::normalised_score is used, which puts each on a scale from random letters at 0.0 to
+    typical text of its language at 1.0.
+
+    Only languages written in A-Z are tried, so Russian never is. A language whose key length
+    search fails (its IOC may be too high for the ciphertext to reach) is just left out, the
+    search only fails when every language does. One that cracks to the wrong key can't win
+    however well its model would have liked the right plaintext.
+*/
+use decode_given_key::Ngram;
+use keyphrase::{Language, Tableau, VigenereError};
+
+use crate::decode_with_model;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageScore {
+    pub language: Language,
+    pub fitness: f64, // Ngram::normalised_score of the plaintext, higher is better
+    pub key: String,
+    pub plaintext: String,
+}
+
+// cracks with every A-Z language in Language::ALL, best fitting first
+pub fn crack_any_language(ciphertext: &str, tableau: Tableau) -> Result<Vec<LanguageScore>, VigenereError> {
+    let mut scores: Vec<LanguageScore> = Vec::new();
+    let mut first_error = None;
+    for language in Language::ALL {
+        let ngram = match Ngram::for_language(language) {
+            Some(ngram) => ngram,
+            None => continue,
+        };
+        match decode_with_model(ciphertext, &ngram, tableau) {
+            Ok((key, plaintext)) => scores.push(LanguageScore { language, fitness: ngram.normalised_score(&plaintext), key, plaintext }),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if scores.is_empty() {
+        return Err(first_error.unwrap());
+    }
+    // stable sort, so equal scores stay in Language::ALL order with English first
+    scores.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+    Ok(scores)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyphrase::KeyPhrase;
+
+    #[test]
+    fn detects_language() {
+        let german = "Am Rande des kleinen Dorfes stand eine alte Muehle, deren Rad sich schon lange nicht mehr drehte. Die Kinder erzaehlten sich, dass dort nachts ein Licht brenne und jemand leise singe. Niemand glaubte daran, bis der Lehrer eines Abends selbst hinging, um der Sache auf den Grund zu gehen. Er fand nur einen alten Mann, der am Fenster sass und in einem dicken Buch las.";
+        let english = "There are two ways of constructing a software design: one way is to make it so simple that there are obviously no deficiencies, and the other way is to make it so complicated that there are no obvious deficiencies. The first method is far more difficult. It demands the same skill, devotion, insight, and even inspiration as the discovery of the simple physical laws which underlie the complex phenomena of nature.";
+        let french = "Longtemps, je me suis couche de bonne heure. Parfois, a peine ma bougie eteinte, mes yeux se fermaient si vite que je n avais pas le temps de me dire: je m endors. Et, une demi heure apres, la pensee qu il etait temps de chercher le sommeil m eveillait; je voulais poser le volume que je croyais avoir encore dans les mains et souffler ma lumiere.";
+        let spanish = "En un lugar de la Mancha, de cuyo nombre no quiero acordarme, no ha mucho tiempo que vivia un hidalgo de los de lanza en astillero, adarga antigua, rocin flaco y galgo corredor. Una olla de algo mas vaca que carnero, salpicon las mas noches, duelos y quebrantos los sabados, lentejas los viernes, algun palomino de anadidura los domingos, consumian las tres partes de su hacienda.";
+        let italian = "Nel mezzo del cammin di nostra vita mi ritrovai per una selva oscura, che la diritta via era smarrita. Ahi quanto a dir qual era e cosa dura esta selva selvaggia e aspra e forte che nel pensier rinova la paura! Tant e amara che poco e piu morte; ma per trattar del ben ch i vi trovai, diro de l altre cose ch i v ho scorte.";
+        let portuguese = "Era uma vez uma pequena cidade a beira do mar, onde os pescadores saiam todas as manhas antes do nascer do sol. As mulheres esperavam no porto com cestos vazios, e as criancas corriam pela areia a procura de conchas. Quando os barcos voltavam, toda a gente se reunia para ver o peixe e ouvir as historias do mar alto.";
+        let samples = [
+            (german, Language::German, "GEHEIM"),
+            (english, Language::English, "GEHEIM"),
+            (french, Language::French, "KEY"),
+            (spanish, Language::Spanish, "SECURITY"),
+            (italian, Language::Italian, "LEMON"),
+            (portuguese, Language::Portuguese, "KEY"),
+        ];
+        for (plaintext, language, key) in samples {
+            let mut keyphrase = KeyPhrase::new(String::from(key)).unwrap();
+            let ciphertext = encode::encode(plaintext, &mut keyphrase);
+            let scores = crack_any_language(&ciphertext, Tableau::Vigenere).unwrap();
+            assert_eq!((scores[0].language, scores[0].key.as_str(), scores[0].plaintext.as_str()), (language, key, plaintext));
+            assert!(scores.windows(2).all(|pair| pair[0].fitness >= pair[1].fitness));
+        }
+        assert!(matches!(crack_any_language("Hi!", Tableau::Vigenere), Err(VigenereError::CiphertextTooShort { .. })));
+    }
+}
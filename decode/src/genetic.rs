@@ -0,0 +1,216 @@
+/*
+    Genetic algorithm for long repeating keys. With a key of 20 or more letters each column has
+    only a handful of ciphertext letters, so chi-squared gets many of them wrong, and the hill
+    climb has to fix them one letter at a time from a poor start. Here a population of keys is
+    evolved instead. Each generation keeps its best few keys as they are (elitism) and breeds the
+    rest: two parents are picked by tournament (the fitter of a few keys drawn at random), the
+    child takes each column's letter from one parent or the other, and then some of its letters
+    are changed at random (mutation). Fitness is the n-gram score of the key's plaintext. A key
+    with several right columns passes them on together, so good columns found in different keys
+    end up in the same one. The first population is the chi-squared key, mutated copies of it and
+    random keys. Crossover and mutation rarely get the last few single letters right, so the best
+    key is finished off by the hill climb, and the key the hill climb finds on its own is taken
+    instead if the evolution never beat it.
+
+    The run stops after the given number of generations, or earlier once the time budget is
+    spent. Without a time budget a seed always gives the same key. With one, how far a run gets
+    depends on the machine.
+
+    https://en.wikipedia.org/wiki/Genetic_algorithm
+    http://practicalcryptography.com/cryptanalysis/stochastic-searching/
+*/
+use std::time::{Duration, Instant};
+
+use decode_given_key::{decode_with_tableau, to_letter_indices, Ngram};
+use decode_given_length::find_key_with_shifts;
+use keyphrase::{group_ciphertext, Alphabet, KeyPhrase, Tableau, VigenereError, ALPHABET_LEN};
+
+use crate::random::Xorshift;
+use crate::{determine_key_length_in, DEFAULT_SEED};
+
+pub const DEFAULT_POPULATION: usize = 100;
+pub const DEFAULT_GENERATIONS: usize = 300;
+pub const DEFAULT_ELITE: usize = 4;
+pub const DEFAULT_MUTATION_RATE: f64 = 0.02;
+pub const DEFAULT_TOURNAMENT: usize = 3;
+
+// everything that decides how an evolution goes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evolution {
+    pub population: usize,             // keys in each generation, at least 2
+    pub generations: usize,            // generations bred after the first
+    pub elite: usize,                  // best keys carried over unchanged, at most the population
+    pub mutation_rate: f64,            // chance of each letter of a child being changed at random
+    pub tournament: usize,             // keys drawn to pick each parent, at least 1
+    pub time_budget: Option<Duration>, // stops the run this long after it started even if generations remain
+    pub seed: u64,
+}
+
+impl Default for Evolution {
+    fn default() -> Evolution {
+        Evolution {
+            population: DEFAULT_POPULATION,
+            generations: DEFAULT_GENERATIONS,
+            elite: DEFAULT_ELITE,
+            mutation_rate: DEFAULT_MUTATION_RATE,
+            tournament: DEFAULT_TOURNAMENT,
+            time_budget: None,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneticSolution {
+    pub key: String,
+    pub score: f64,         // Ngram::score_letters of the plaintext's letters
+    pub generations: usize, // bred before the run stopped
+    pub plaintext: String,
+}
+
+// cracks with the key length from determine_key_length_in, or key_length when given
+pub fn crack_genetic(ciphertext: &str, ngram: &Ngram, tableau: Tableau, key_length: Option<usize>, evolution: &Evolution) -> Result<GeneticSolution, VigenereError> {
+    let buckets = match key_length {
+        Some(0) => return Err(VigenereError::ZeroKeyLength),
+        Some(key_length) => group_ciphertext(&ciphertext.to_ascii_uppercase(), key_length),
+        None => determine_key_length_in(ciphertext, &Alphabet::letters(), ngram.language())?.1,
+    };
+    let letters = to_letter_indices(ciphertext);
+    if letters.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed: ngram.ngram_length() });
+    }
+
+    let stripped_ciphertext: String = letters.iter().map(|&letter| (letter + b'A') as char).collect();
+    let parent = find_key_with_shifts(&buckets, tableau, ALPHABET_LEN as u8, ngram.language());
+    let (evolved, _, generations) = evolve_key(&letters, &to_letter_indices(&parent), ngram, tableau, evolution);
+    // crossover and random mutation rarely fix the last few single letters, which the hill climb does,
+    // and the key the usual crack gets to is kept if the evolution never beat it
+    let mut plaintext = vec![0; letters.len()];
+    let (key, score) = [evolved, parent]
+        .into_iter()
+        .map(|key| ngram.generate_key_from_parent_with_tableau(key, stripped_ciphertext.clone(), tableau))
+        .map(|key| {
+            let score = ngram.score_key(&to_letter_indices(&key), &letters, &mut plaintext, tableau);
+            (key, score)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    let mut keyphrase = KeyPhrase::new(key.clone())?;
+    Ok(GeneticSolution { plaintext: decode_with_tableau(ciphertext, &mut keyphrase, tableau)?, key, score, generations })
+}
+
+// evolves keys as long as parent (shifts 0-25) over the ciphertext's letters, returning the
+// best key, its score and how many generations were bred
+pub fn evolve_key(ciphertext: &[u8], parent: &[u8], ngram: &Ngram, tableau: Tableau, evolution: &Evolution) -> (String, f64, usize) {
+    let started = Instant::now();
+    let mut rng = Xorshift::new(evolution.seed);
+    let size = usize::max(evolution.population, 2);
+    let elite = usize::min(evolution.elite, size);
+    let mut plaintext = vec![0; ciphertext.len()];
+
+    // the chi-squared key, then half mutated copies of it and half random keys
+    let mut population: Vec<(Vec<u8>, f64)> = Vec::with_capacity(size);
+    for i in 0..size {
+        let key: Vec<u8> = match i {
+            0 => parent.to_vec(),
+            _ if i % 2 == 1 => parent.iter().map(|&letter| mutate(letter, evolution.mutation_rate, &mut rng)).collect(),
+            _ => (0..parent.len()).map(|_| rng.below(ALPHABET_LEN) as u8).collect(),
+        };
+        let score = ngram.score_key(&key, ciphertext, &mut plaintext, tableau);
+        population.push((key, score));
+    }
+    population.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut generations = 0;
+    while generations < evolution.generations && evolution.time_budget.map_or(true, |budget| started.elapsed() < budget) {
+        let mut next: Vec<(Vec<u8>, f64)> = population[..elite].to_vec();
+        while next.len() < size {
+            let mother = &population[select(&population, evolution.tournament, &mut rng)].0;
+            let father = &population[select(&population, evolution.tournament, &mut rng)].0;
+            // each column from either parent, then mutated
+            let child: Vec<u8> = mother
+                .iter()
+                .zip(father)
+                .map(|(&m, &f)| {
+                    let letter = if rng.below(2) == 0 { m } else { f };
+                    mutate(letter, evolution.mutation_rate, &mut rng)
+                })
+                .collect();
+            let score = ngram.score_key(&child, ciphertext, &mut plaintext, tableau);
+            next.push((child, score));
+        }
+        next.sort_by(|a, b| b.1.total_cmp(&a.1));
+        population = next;
+        generations += 1;
+    }
+
+    let (key, score) = &population[0];
+    (key.iter().map(|&shift| (shift + b'A') as char).collect(), *score, generations)
+}
+
+// index of the fittest of tournament keys drawn at random, the population being sorted best first
+fn select(population: &[(Vec<u8>, f64)], tournament: usize, rng: &mut Xorshift) -> usize {
+    (0..usize::max(tournament, 1)).map(|_| rng.below(population.len())).min().unwrap()
+}
+
+fn mutate(letter: u8, rate: f64, rng: &mut Xorshift) -> u8 {
+    if rng.unit() < rate {
+        rng.below(ALPHABET_LEN) as u8
+    }
+    else {
+        letter
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encode::encode;
+    use keyphrase::Language;
+
+    const PLAINTEXT: &str = "Paragraphs are the building blocks of papers. Many students define paragraphs in terms of length: a paragraph is a group of at least five sentences, a paragraph is half a page long, etc. In reality, though, the unity and coherence of ideas among sentences is what constitutes a paragraph.";
+
+    #[test]
+    fn evolves_long_key() {
+        // about seven letters a column, where the hill climb gets some of them wrong
+        let key = "ABRACADABRAMAGICWORDSPOKENSOFTLY";
+        let ciphertext = encode(PLAINTEXT, &mut KeyPhrase::new(String::from(key)).unwrap());
+        let buckets = group_ciphertext(&ciphertext.to_ascii_uppercase(), key.len());
+        assert_ne!(decode_given_length::decode_given_length(&ciphertext, key.len(), buckets).unwrap().0, key);
+
+        let solution = crack_genetic(&ciphertext, &Ngram::english(), Tableau::Vigenere, Some(key.len()), &Evolution::default()).unwrap();
+        assert_eq!((solution.key.as_str(), solution.plaintext.as_str(), solution.generations), (key, PLAINTEXT, DEFAULT_GENERATIONS));
+    }
+
+    #[test]
+    fn evolves_fitter_key() {
+        let key = "ABRACADABRAMAGICWORDSPOKENSOFTLY";
+        let ciphertext = encode(PLAINTEXT, &mut KeyPhrase::new(String::from(key)).unwrap());
+        let letters = to_letter_indices(&ciphertext);
+        let buckets = group_ciphertext(&ciphertext.to_ascii_uppercase(), key.len());
+        let parent = to_letter_indices(&find_key_with_shifts(&buckets, Tableau::Vigenere, ALPHABET_LEN as u8, Language::English));
+        let ngram = Ngram::english();
+        let mut plaintext = vec![0; letters.len()];
+        let parent_score = ngram.score_key(&parent, &letters, &mut plaintext, Tableau::Vigenere);
+
+        // the chi-squared key has half its letters wrong, the evolution puts all of them right without the hill climb
+        let (evolved, score, generations) = evolve_key(&letters, &parent, &ngram, Tableau::Vigenere, &Evolution::default());
+        assert_eq!(parent.iter().zip(to_letter_indices(key)).filter(|&(&a, b)| a != b).count(), 16);
+        assert_eq!(evolved, key);
+        assert!(score > parent_score);
+        assert_eq!(score, ngram.score_key(&to_letter_indices(&evolved), &letters, &mut plaintext, Tableau::Vigenere));
+        assert_eq!(generations, DEFAULT_GENERATIONS);
+        // the same seed evolves the same key
+        assert_eq!(evolve_key(&letters, &parent, &ngram, Tableau::Vigenere, &Evolution::default()), (evolved, score, generations));
+    }
+
+    #[test]
+    fn stops_at_time_budget() {
+        let ciphertext = encode(PLAINTEXT, &mut KeyPhrase::new(String::from("SECURITY")).unwrap());
+        let evolution = Evolution { time_budget: Some(Duration::ZERO), ..Evolution::default() };
+        let solution = crack_genetic(&ciphertext, &Ngram::english(), Tableau::Vigenere, None, &evolution).unwrap();
+        // nothing bred, so it's the hill climb's key
+        assert_eq!((solution.key.as_str(), solution.generations), ("SECURITY", 0));
+    }
+}
@@ -0,0 +1,152 @@
+/*
+    Kasiski examination: the same plaintext fragment enciphered by the same part of the key gives
+    the same ciphertext fragment, so the distance between repeated ciphertext sequences tends to
+    be a multiple of the key length. Only repeats of MIN_REPEAT_LEN letters or more are counted,
+    shorter ones happen by chance far too often.
+
+    https://en.wikipedia.org/wiki/Kasiski_examination
+    https://pages.mtu.edu/~shene/NSF-4/Tutorial/VIG/Vig-Kasiski.html
+*/
+use std::collections::{BTreeSet, HashMap};
+use std::ops::RangeInclusive;
+
+pub const MIN_REPEAT_LEN: usize = 3;
+
+// a sequence found more than once in the stripped ciphertext
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeat {
+    pub sequence: String,
+    pub positions: Vec<usize>, // letter offsets into the ciphertext with everything but letters removed
+    pub spacings: Vec<usize>,  // distance between each occurrence and the next
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KasiskiPeriod {
+    pub length: usize,
+    pub count: usize, // spacings this length divides
+    pub score: f64,   // how far count is above chance, in standard deviations
+}
+
+/*
+    Finds every repeated sequence of at least MIN_REPEAT_LEN letters. Each pair of matching
+    trigrams is extended to the right as far as the two occurrences agree, and pairs that could
+    also be extended to the left are skipped, so only the longest form of a repeat is reported
+    rather than all of its trigrams. Longest repeats come first.
+*/
+pub fn find_repeats(ciphertext: &str) -> Vec<Repeat> {
+    let text: Vec<u8> = ciphertext.bytes().filter(|b| b.is_ascii_alphabetic()).map(|b| b.to_ascii_uppercase()).collect();
+    if text.len() < MIN_REPEAT_LEN {
+        return Vec::new();
+    }
+
+    let mut starts: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for i in 0..=text.len() - MIN_REPEAT_LEN {
+        starts.entry(&text[i..i + MIN_REPEAT_LEN]).or_default().push(i);
+    }
+
+    let mut found: HashMap<&[u8], BTreeSet<usize>> = HashMap::new();
+    for positions in starts.values().filter(|positions| positions.len() > 1) {
+        for (n, &p) in positions.iter().enumerate() {
+            for &q in &positions[n + 1..] {
+                if p > 0 && text[p - 1] == text[q - 1] {
+                    continue; // part of a longer repeat starting one letter earlier
+                }
+                let mut len = MIN_REPEAT_LEN;
+                while q + len < text.len() && text[p + len] == text[q + len] {
+                    len += 1;
+                }
+                let occurrences = found.entry(&text[p..p + len]).or_default();
+                occurrences.insert(p);
+                occurrences.insert(q);
+            }
+        }
+    }
+
+    let mut repeats: Vec<Repeat> = found
+        .into_iter()
+        .map(|(sequence, positions)| {
+            let positions: Vec<usize> = positions.into_iter().collect();
+            let spacings = positions.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            Repeat { sequence: String::from_utf8(sequence.to_vec()).unwrap(), positions, spacings }
+        })
+        .collect();
+    repeats.sort_by(|a, b| b.sequence.len().cmp(&a.sequence.len()).then(a.positions.cmp(&b.positions)));
+    repeats
+}
+
+// how many of the spacings each length in the range divides, lengths below 2 divide everything and are skipped
+pub fn factor_counts(repeats: &[Repeat], lengths: RangeInclusive<usize>) -> Vec<(usize, usize)> {
+    let shortest = usize::max(*lengths.start(), 2);
+    (shortest..=*lengths.end())
+        .map(|length| {
+            let count = repeats.iter().flat_map(|r| r.spacings.iter()).filter(|&&spacing| spacing % length == 0).count();
+            (length, count)
+        })
+        .collect()
+}
+
+/*
+    Ranks the lengths in the range as likely key periods. A raw factor count always favours small
+    lengths (2 divides half of all spacings by chance), so each count is compared with the n/length
+    spacings a random distance would give and scored as a binomial z-score. The real period and
+    its multiples divide every true spacing, and the real period wins because chance explains less
+    of its count than of its factors' counts. Lengths no spacing divides are left out.
+*/
+pub fn rank_periods(repeats: &[Repeat], lengths: RangeInclusive<usize>) -> Vec<KasiskiPeriod> {
+    let total = repeats.iter().map(|r| r.spacings.len()).sum::<usize>() as f64;
+
+    let mut periods: Vec<KasiskiPeriod> = factor_counts(repeats, lengths)
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(length, count)| {
+            let p = 1.0 / length as f64;
+            let score = (count as f64 - total * p) / (total * p * (1.0 - p)).sqrt();
+            KasiskiPeriod { length, count, score }
+        })
+        .collect();
+    // stable sort, so equal scores stay shortest first
+    periods.sort_by(|a, b| b.score.total_cmp(&a.score));
+    periods
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_maximal_repeats() {
+        // ABCD repeats 6 apart, QQQ overlaps itself
+        let repeats = find_repeats("abcd xy ABCD, zQQQQ");
+        assert_eq!(repeats[0], Repeat { sequence: String::from("ABCD"), positions: vec![0, 6], spacings: vec![6] });
+        assert!(!repeats.iter().any(|r| r.sequence == "BCD" || r.sequence == "ABC"));
+        assert!(repeats.iter().any(|r| r.sequence == "QQQ" && r.positions == vec![11, 12]));
+
+        assert!(find_repeats("AB").is_empty());
+        assert!(find_repeats("ABCDEFGHIJ").is_empty());
+    }
+
+    #[test]
+    fn summer_period() {
+        // key is SUMMER
+        let ciphertext = "TYWUR USHPO SLJNQ AYJLI FTMJY YZFPV EUZTS GAHTU WNSFW EEEVA
+        MYFFD CZTMJ WSQEJ VWXTU QNANT MTIAW AOOJS HPPIN TYDDM VKQUF
+        LGMLB XIXJU BQWXJ YQZJZ YMMZH DMFNQ VIAYE FLVZI ZQCSS AEEXV
+        SFRDS DLBQT YDTFQ NIVKU ZPJFJ HUSLK LUBQV JULAB XYWCD IEOWH
+        FTMXZ MMZHC AATFX YWGMF XYWZU QVPYF AIAFJ GEQCV KNATE MWGKX
+        SMWNA NIUSH PFSRJ CEQEE VJXGG BLBQI MEYMR DSDHU UZXVV VGFXV
+        JZXUI JLIRM RKZYY ASETY MYWWJ IYTMJ KFQQT ZFAQK IJFIP FSYAG
+        QXZVK UZPHF ZCYOS LJNQE MVK";
+        let repeats = find_repeats(ciphertext);
+        let sljnq = repeats.iter().find(|r| r.sequence.contains("SLJNQ")).unwrap();
+        assert!(sljnq.spacings.iter().all(|s| s % 6 == 0));
+
+        let counts = factor_counts(&repeats, 1..=10);
+        assert_eq!(counts[0].0, 2);
+        assert_eq!(counts.len(), 9);
+
+        let periods = rank_periods(&repeats, 1..=40);
+        assert_eq!(periods[0].length, 6);
+        assert!(periods.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+}
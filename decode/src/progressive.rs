@@ -0,0 +1,135 @@
+/*
+    Cracking a progressive key. Every shift moves on by the step after each pass through the key,
+    so a column of the ciphertext is no longer one alphabet and the IOC finds nothing at the key
+    length. Undoing the progression fixes that: with the right key length L and step s, moving
+    ciphertext letter i back by s * (i / L) leaves ordinary periodic ciphertext whose columns
+    look like the plaintext language again. So every step is tried at every key length, shortest first, and the
+    first length where one of them gives such columns is taken along with the step that gave
+    the most plaintext looking ones. From there it's a repeating key, cracked as usual.
+
+    Moving a letter back by a shift only undoes a key that much further on when the tableau adds
+    key letters up, as Vigenere and both Beauforts do. Porta's alphabets don't, so it's refused.
+    Step 0 is a plain repeating key, and the Trithemius cipher is key A with step 1.
+*/
+use std::ops::RangeInclusive;
+
+use decode_given_key::{decode_with_tableau, Ngram};
+use decode_given_length::decode_given_length_with_model;
+use keyphrase::{calculate_ioc, group_ciphertext, KeyPhrase, Language, Tableau, VigenereError, ALPHABET_LEN};
+
+use crate::{DEFAULT_LENGTH_RANGE, DELTA, MIN_LETTERS_PER_COLUMN};
+
+pub const DEFAULT_STEPS: RangeInclusive<u8> = 0..=ALPHABET_LEN as u8 - 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressiveSolution {
+    pub key: String,
+    pub step: u8,
+    pub plaintext: String,
+}
+
+// cracks with the key length and step from find_progression, either of which can be fixed instead
+pub fn crack_progressive(ciphertext: &str, ngram: &Ngram, tableau: Tableau, key_length: Option<usize>, step: Option<u8>) -> Result<ProgressiveSolution, VigenereError> {
+    let (key_length, step) = find_progression(ciphertext, tableau, key_length, step, ngram.language())?;
+    let periodic = remove_progression(ciphertext, tableau, key_length, step);
+    let buckets = group_ciphertext(&periodic.to_ascii_uppercase(), key_length);
+    let (key, _) = decode_given_length_with_model(&periodic, key_length, buckets, ngram, tableau)?;
+
+    let mut keyphrase = KeyPhrase::progressive(key.clone(), step)?;
+    Ok(ProgressiveSolution { plaintext: decode_with_tableau(ciphertext, &mut keyphrase, tableau)?, key, step })
+}
+
+// the shortest key length up to DEFAULT_LENGTH_RANGE's end where undoing some step leaves columns
+// with about language's IOC, and that step
+pub fn find_progression(ciphertext: &str, tableau: Tableau, key_length: Option<usize>, step: Option<u8>, language: Language) -> Result<(usize, u8), VigenereError> {
+    if tableau == Tableau::Porta {
+        return Err(VigenereError::UnsupportedTableau(tableau));
+    }
+    let letters = ciphertext.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    let longest = usize::min(letters / MIN_LETTERS_PER_COLUMN, *DEFAULT_LENGTH_RANGE.end());
+    let lengths = match key_length {
+        Some(0) => return Err(VigenereError::ZeroKeyLength),
+        Some(key_length) => key_length..=key_length,
+        None => 1..=longest,
+    };
+    let steps = match step {
+        Some(step) => {
+            let step = step % ALPHABET_LEN as u8;
+            step..=step
+        }
+        None => DEFAULT_STEPS,
+    };
+
+    for length in lengths {
+        let (step, ioc) = steps
+            .clone()
+            .map(|step| (step, average_ioc(&remove_progression(ciphertext, tableau, length, step), length)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        // a length that was asked for is used whatever its columns look like
+        if key_length.is_some() || language.ioc() - DELTA <= ioc {
+            return Ok((length, step));
+        }
+    }
+    Err(VigenereError::CiphertextTooShort { letters, needed: (longest + 1) * MIN_LETTERS_PER_COLUMN })
+}
+
+// the ciphertext as it would be with a plain repeating key, case and punctuation kept
+fn remove_progression(ciphertext: &str, tableau: Tableau, key_length: usize, step: u8) -> String {
+    let mut i = 0;
+    ciphertext
+        .chars()
+        .map(|c| {
+            let base = if c.is_ascii_lowercase() {
+                b'a'
+            }
+            else if c.is_ascii_uppercase() {
+                b'A'
+            }
+            else {
+                return c;
+            };
+            let advance = ((i / key_length) * step as usize % ALPHABET_LEN) as u8;
+            i += 1;
+            // decrypting with the advance and encrypting with A takes the advance off the key
+            (tableau.encrypt(tableau.decrypt(c as u8 - base, advance), 0) + base) as char
+        })
+        .collect()
+}
+
+fn average_ioc(ciphertext: &str, key_length: usize) -> f64 {
+    let buckets = group_ciphertext(&ciphertext.to_ascii_uppercase(), key_length);
+    buckets.iter().map(calculate_ioc).sum::<f64>() / buckets.len() as f64
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encode::encode_with_tableau;
+
+    const PLAINTEXT: &str = "There are two ways of constructing a software design: one way is to make it so simple that there are obviously no deficiencies, and the other way is to make it so complicated that there are no obvious deficiencies. The first method is far more difficult. It demands the same skill, devotion, insight, and even inspiration as the discovery of the simple physical laws which underlie the complex phenomena of nature.";
+
+    #[test]
+    fn cracks_progressive() {
+        for tableau in [Tableau::Vigenere, Tableau::Beaufort, Tableau::VariantBeaufort] {
+            let mut keyphrase = KeyPhrase::progressive(String::from("FORTIFY"), 3).unwrap();
+            let ciphertext = encode_with_tableau(PLAINTEXT, &mut keyphrase, tableau).unwrap();
+            let solution = crack_progressive(&ciphertext, &Ngram::english(), tableau, None, None).unwrap();
+            assert_eq!((solution.key.as_str(), solution.step), ("FORTIFY", 3));
+            assert_eq!(solution.plaintext, PLAINTEXT);
+        }
+
+        let ciphertext = encode_with_tableau(PLAINTEXT, &mut KeyPhrase::trithemius(), Tableau::Vigenere).unwrap();
+        assert_eq!(find_progression(&ciphertext, Tableau::Vigenere, None, None, Language::English), Ok((1, 1)));
+        let solution = crack_progressive(&ciphertext, &Ngram::english(), Tableau::Vigenere, None, None).unwrap();
+        assert_eq!(solution.plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn progression_errors() {
+        assert_eq!(find_progression("ABCDEFGH", Tableau::Porta, None, None, Language::English), Err(VigenereError::UnsupportedTableau(Tableau::Porta)));
+        assert_eq!(find_progression("ABCDEFGH", Tableau::Vigenere, Some(0), None, Language::English), Err(VigenereError::ZeroKeyLength));
+        assert_eq!(find_progression("ABCDEFGH", Tableau::Vigenere, Some(2), Some(29), Language::English), Ok((2, 3)));
+    }
+}
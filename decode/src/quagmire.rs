@@ -0,0 +1,490 @@
+/*
+    Cracking Quagmire ciphertext. Each column of a period L Quagmire is still a single
+    substitution alphabet, so determine_key_length finds L just as it does for Vigenere, but the
+    substitutions are no longer shifts of A-Z and chi-squared can't read the key off them.
+
+    Given a guess at the keyed alphabets though, every column is a shift again: ciphertext
+    letter c in column j decrypts to plain[cipher position of c - shift j]. So the search is
+    over the alphabets, with each column's shift picked to suit whatever alphabets are being
+    tried. That happens in two stages. Letter frequencies come first, as they only need the
+    column counts: letters are swapped around the alphabets, with each shift set to whatever
+    makes its column's letters most English. The n-gram model then takes over from there,
+    moving letters and changing shifts until no single change improves the plaintext. Both
+    climbs are kicked out of local optima by swapping a few letters at random and climbing
+    again, keeping whichever ends up better.
+
+    Only the alphabets the type keys are searched: the plaintext alphabet for I, the ciphertext
+    alphabet for II, one alphabet used for both for III and the two separately for IV. Many
+    alphabet pairs give the same decryption (sliding an alphabet along just changes the key),
+    so the alphabets found decrypt correctly but needn't be the keyword alphabets that were
+    used. The key is reported with the first letter of the plaintext alphabet as the indicator.
+
+    One keyed alphabet is usually found from a few hundred letters. Letter frequencies say much
+    less when both alphabets are keyed, so III and IV need a good deal more ciphertext, and
+    longer keys need more as each column gets fewer letters.
+
+    http://www.cryptogram.org/downloads/aca.info/ciphers/Quagmire.pdf
+    http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-simple-substitution-cipher/
+*/
+use decode_given_key::{decode_quagmire, to_letter_indices, Ngram};
+use keyphrase::{Alphabet, KeyPhrase, Quagmire, QuagmireKind, VigenereError, ALPHABET_LEN, STRAIGHT_ALPHABET};
+
+use crate::random::Xorshift;
+use crate::{determine_key_length_in, DEFAULT_SEED};
+
+const FREQUENCY_PATIENCE: usize = 1000; // kicks in a row without improvement before the letter frequency climb stops
+const NGRAM_PATIENCE: usize = 10; // and the same for the n-gram climb
+const KICK_SWAPS: usize = 3; // letters swapped at random to shake up an alphabet
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuagmireSolution {
+    pub key: String,
+    pub plain_alphabet: String,
+    pub cipher_alphabet: String,
+    pub indicator: char,
+    pub plaintext: String,
+}
+
+// cracks with the key length from determine_key_length_in, or key_length when given
+pub fn crack_quagmire(ciphertext: &str, ngram: &Ngram, kind: QuagmireKind, key_length: Option<usize>) -> Result<QuagmireSolution, VigenereError> {
+    let key_length = match key_length {
+        Some(0) => return Err(VigenereError::ZeroKeyLength),
+        Some(key_length) => key_length,
+        None => determine_key_length_in(ciphertext, &Alphabet::letters(), ngram.language())?.0,
+    };
+    let letters = to_letter_indices(ciphertext);
+    let needed = usize::max(ngram.ngram_length(), key_length);
+    if letters.len() < needed {
+        return Err(VigenereError::CiphertextTooShort { letters: letters.len(), needed });
+    }
+
+    let best = Search::new(&letters, key_length, ngram, kind).climb();
+
+    let quagmire = Quagmire::from_alphabets(best.plain, best.cipher, best.plain[0]);
+    let key: String = best.shifts.iter().map(|&s| (quagmire.key_letter(s) + b'A') as char).collect();
+    let mut keyphrase = KeyPhrase::new(key.clone())?;
+    Ok(QuagmireSolution {
+        plaintext: decode_quagmire(ciphertext, &quagmire, &mut keyphrase)?,
+        key,
+        plain_alphabet: quagmire.plain_alphabet(),
+        cipher_alphabet: quagmire.cipher_alphabet(),
+        indicator: (best.plain[0] + b'A') as char,
+    })
+}
+
+// a pair of alphabets as permutations of 0..=25 and the column shifts that go with them
+struct Candidate {
+    plain: [u8; ALPHABET_LEN],
+    cipher: [u8; ALPHABET_LEN],
+    shifts: Vec<u8>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Fitness {
+    LetterFrequencies, // how English each column's letters are, with its best shift
+    Ngrams,            // the n-gram score of the plaintext with the current shifts
+}
+
+// the ciphertext and buffers shared by every climb
+struct Search<'a> {
+    ciphertext: &'a [u8],
+    counts: Vec<[usize; ALPHABET_LEN]>, // ciphertext letter counts per column
+    log_frequencies: [f64; ALPHABET_LEN],
+    ngram: &'a Ngram,
+    kind: QuagmireKind,
+    moves: Vec<Move>,
+    shifts: Vec<u8>,
+    plaintext: Vec<u8>,
+    rng: Xorshift,
+}
+
+impl<'a> Search<'a> {
+    fn new(ciphertext: &'a [u8], key_length: usize, ngram: &'a Ngram, kind: QuagmireKind) -> Search<'a> {
+        let mut counts = vec![[0; ALPHABET_LEN]; key_length];
+        for (i, &c) in ciphertext.iter().enumerate() {
+            counts[i % key_length][c as usize] += 1;
+        }
+        Search {
+            ciphertext,
+            counts,
+            log_frequencies: log_frequencies(ngram),
+            ngram,
+            kind,
+            moves: moves(),
+            shifts: vec![0; key_length],
+            plaintext: vec![0; ciphertext.len()],
+            rng: Xorshift::new(DEFAULT_SEED), // fixed so the same ciphertext always cracks the same way
+        }
+    }
+
+    // letter frequencies first, then n-grams from where that got to
+    fn climb(&mut self) -> Candidate {
+        let mut plain = STRAIGHT_ALPHABET;
+        let mut cipher = STRAIGHT_ALPHABET;
+        self.kicked_climb(&mut plain, &mut cipher, Fitness::LetterFrequencies, FREQUENCY_PATIENCE);
+        self.pick_shifts(&plain, &cipher);
+        self.kicked_climb(&mut plain, &mut cipher, Fitness::Ngrams, NGRAM_PATIENCE);
+        Candidate { plain, cipher, shifts: self.shifts.clone() }
+    }
+
+    /*
+        Climbs, then repeatedly swaps a few letters of the best alphabets found so far and climbs
+        again from there, keeping the result if it's better, until patience kicks in a row have
+        found nothing. Climbs stall where no single move helps, and a small kick usually gets
+        past that without throwing the progress away.
+    */
+    fn kicked_climb(&mut self, plain: &mut [u8; ALPHABET_LEN], cipher: &mut [u8; ALPHABET_LEN], fitness: Fitness, patience: usize) -> f64 {
+        let mut best_score = self.climb_with(plain, cipher, fitness);
+        let mut best_shifts = self.shifts.clone();
+        let mut fruitless = 0;
+        while fruitless < patience {
+            let mut kicked_plain = *plain;
+            let mut kicked_cipher = *cipher;
+            self.kick(&mut kicked_plain, &mut kicked_cipher);
+            let score = self.climb_with(&mut kicked_plain, &mut kicked_cipher, fitness);
+            if score > best_score {
+                best_score = score;
+                *plain = kicked_plain;
+                *cipher = kicked_cipher;
+                best_shifts.clone_from(&self.shifts);
+                fruitless = 0;
+            }
+            else {
+                self.shifts.clone_from(&best_shifts);
+                fruitless += 1;
+            }
+        }
+        best_score
+    }
+
+    // swaps random letters in whichever alphabets the type keys, III swapping the same ones in both
+    fn kick(&mut self, plain: &mut [u8; ALPHABET_LEN], cipher: &mut [u8; ALPHABET_LEN]) {
+        for _ in 0..KICK_SWAPS {
+            let i = self.rng.below(ALPHABET_LEN);
+            let j = self.rng.below(ALPHABET_LEN);
+            match self.kind {
+                QuagmireKind::I => plain.swap(i, j),
+                QuagmireKind::II => cipher.swap(i, j),
+                QuagmireKind::III => {
+                    plain.swap(i, j);
+                    cipher.swap(i, j);
+                }
+                QuagmireKind::IV => {
+                    if self.rng.below(2) == 0 {
+                        plain.swap(i, j);
+                    }
+                    else {
+                        cipher.swap(i, j);
+                    }
+                }
+            }
+        }
+    }
+
+    // which alphabets a move applies to, IV moves letters in one at a time and III in both at once so they stay the same
+    fn targets(&self) -> &'static [(bool, bool)] {
+        match self.kind {
+            QuagmireKind::I => &[(true, false)],
+            QuagmireKind::II => &[(false, true)],
+            QuagmireKind::III => &[(true, true)],
+            QuagmireKind::IV => &[(true, false), (false, true)],
+        }
+    }
+
+    fn climb_with(&mut self, plain: &mut [u8; ALPHABET_LEN], cipher: &mut [u8; ALPHABET_LEN], fitness: Fitness) -> f64 {
+        match fitness {
+            Fitness::LetterFrequencies => self.climb_frequencies(plain, cipher),
+            Fitness::Ngrams => self.climb_ngrams(plain, cipher),
+        }
+    }
+
+    /*
+        Greedy climb over letter swaps for how English the columns' letters are. Each column keeps
+        its likelihood at every shift, and a swap only changes the terms for the positions it
+        touches, so trying one costs a few terms per shift rather than redoing the lot. Only
+        which letters share a position matters here, not their order, so swaps are all it needs.
+    */
+    fn climb_frequencies(&mut self, plain: &mut [u8; ALPHABET_LEN], cipher: &mut [u8; ALPHABET_LEN]) -> f64 {
+        let mut likelihoods: Vec<[f64; ALPHABET_LEN]> = (0..self.counts.len())
+            .map(|column| {
+                let mut by_shift = [0.0; ALPHABET_LEN];
+                for (shift, likelihood) in by_shift.iter_mut().enumerate() {
+                    *likelihood = (0..ALPHABET_LEN).map(|position| self.term(plain, cipher, column, position, shift)).sum();
+                }
+                by_shift
+            })
+            .collect();
+        let mut trial = likelihoods.clone();
+        let mut best_score = total_best(&likelihoods);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..ALPHABET_LEN {
+                for j in i + 1..ALPHABET_LEN {
+                    for &(in_plain, in_cipher) in self.targets() {
+                        trial.clone_from(&likelihoods);
+                        self.add_swap_terms(&mut trial, plain, cipher, (i, j), in_plain, in_cipher, -1.0);
+                        if in_plain {
+                            plain.swap(i, j);
+                        }
+                        if in_cipher {
+                            cipher.swap(i, j);
+                        }
+                        self.add_swap_terms(&mut trial, plain, cipher, (i, j), in_plain, in_cipher, 1.0);
+                        let score = total_best(&trial);
+                        if score > best_score {
+                            best_score = score;
+                            std::mem::swap(&mut likelihoods, &mut trial);
+                            improved = true;
+                        }
+                        else {
+                            if in_plain {
+                                plain.swap(i, j);
+                            }
+                            if in_cipher {
+                                cipher.swap(i, j);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best_score
+    }
+
+    // adds sign times the terms a swap of positions i and j changes, at every shift of every column
+    #[allow(clippy::too_many_arguments)]
+    fn add_swap_terms(&self, likelihoods: &mut [[f64; ALPHABET_LEN]], plain: &[u8; ALPHABET_LEN], cipher: &[u8; ALPHABET_LEN], (i, j): (usize, usize), in_plain: bool, in_cipher: bool, sign: f64) {
+        for shift in 0..ALPHABET_LEN {
+            // ciphertext positions whose term changes: the swapped ones, and those lined up with the swapped plaintext positions
+            let mut positions = [0; 4];
+            let mut len = 0;
+            let mut touched = [false; ALPHABET_LEN];
+            let swapped_cipher = if in_cipher { [i, j] } else { [ALPHABET_LEN; 2] };
+            let swapped_plain = if in_plain { [(i + shift) % ALPHABET_LEN, (j + shift) % ALPHABET_LEN] } else { [ALPHABET_LEN; 2] };
+            for position in swapped_cipher.into_iter().chain(swapped_plain) {
+                if position < ALPHABET_LEN && !touched[position] {
+                    touched[position] = true;
+                    positions[len] = position;
+                    len += 1;
+                }
+            }
+            for (column, by_shift) in likelihoods.iter_mut().enumerate() {
+                for &position in &positions[..len] {
+                    by_shift[shift] += sign * self.term(plain, cipher, column, position, shift);
+                }
+            }
+        }
+    }
+
+    // log likelihood of the ciphertext letters at one position of the ciphertext alphabet, decrypted with shift
+    fn term(&self, plain: &[u8; ALPHABET_LEN], cipher: &[u8; ALPHABET_LEN], column: usize, position: usize, shift: usize) -> f64 {
+        let count = self.counts[column][cipher[position] as usize];
+        count as f64 * self.log_frequencies[plain[(position + ALPHABET_LEN - shift) % ALPHABET_LEN] as usize]
+    }
+
+    // greedy climb over moves in whichever alphabets the type keys and over the shifts, for the n-gram score
+    fn climb_ngrams(&mut self, plain: &mut [u8; ALPHABET_LEN], cipher: &mut [u8; ALPHABET_LEN]) -> f64 {
+        let mut best_score = self.decrypt(plain, cipher);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..self.moves.len() {
+                let mv = self.moves[i];
+                for &(in_plain, in_cipher) in self.targets() {
+                    if in_plain {
+                        rearrange(plain, mv);
+                    }
+                    if in_cipher {
+                        rearrange(cipher, mv);
+                    }
+                    let score = self.decrypt(plain, cipher);
+                    if score > best_score {
+                        best_score = score;
+                        improved = true;
+                    }
+                    else {
+                        if in_plain {
+                            rearrange(plain, mv.inverse());
+                        }
+                        if in_cipher {
+                            rearrange(cipher, mv.inverse());
+                        }
+                    }
+                }
+            }
+            for column in 0..self.shifts.len() {
+                let mut best_shift = self.shifts[column];
+                for shift in 0..ALPHABET_LEN as u8 {
+                    self.shifts[column] = shift;
+                    let score = self.decrypt(plain, cipher);
+                    if score > best_score {
+                        best_score = score;
+                        best_shift = shift;
+                        improved = true;
+                    }
+                }
+                self.shifts[column] = best_shift;
+            }
+        }
+        best_score
+    }
+
+    // sets each column's shift to the one whose plaintext letters are most likely English, returning the total log likelihood
+    fn pick_shifts(&mut self, plain: &[u8; ALPHABET_LEN], cipher: &[u8; ALPHABET_LEN]) -> f64 {
+        let mut total = 0.0;
+        for (column, counts) in self.counts.iter().enumerate() {
+            let mut best = f64::MIN;
+            for shift in 0..ALPHABET_LEN {
+                let likelihood: f64 = cipher
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &c)| counts[c as usize] as f64 * self.log_frequencies[plain[(position + ALPHABET_LEN - shift) % ALPHABET_LEN] as usize])
+                    .sum();
+                if likelihood > best {
+                    best = likelihood;
+                    self.shifts[column] = shift as u8;
+                }
+            }
+            total += best;
+        }
+        total
+    }
+
+    // the n-gram score of the ciphertext decrypted with the current shifts
+    fn decrypt(&mut self, plain: &[u8; ALPHABET_LEN], cipher: &[u8; ALPHABET_LEN]) -> f64 {
+        let mut cipher_position = [0; ALPHABET_LEN];
+        for (position, &c) in cipher.iter().enumerate() {
+            cipher_position[c as usize] = position;
+        }
+        let key_length = self.shifts.len();
+        for (i, (p, &c)) in self.plaintext.iter_mut().zip(self.ciphertext).enumerate() {
+            let shift = self.shifts[i % key_length] as usize;
+            *p = plain[(cipher_position[c as usize] + ALPHABET_LEN - shift) % ALPHABET_LEN];
+        }
+        self.ngram.score_letters(&self.plaintext)
+    }
+}
+
+// log of how often each letter A-Z turns up in the model's language, evenly spread for one not in A-Z
+fn log_frequencies(ngram: &Ngram) -> [f64; ALPHABET_LEN] {
+    let language = ngram.language();
+    if !language.is_latin() {
+        return [(1.0 / ALPHABET_LEN as f64).ln(); ALPHABET_LEN];
+    }
+    let frequencies = language.letter_frequencies();
+    std::array::from_fn(|letter| frequencies[letter].ln())
+}
+
+// each column's likelihood at its best shift, summed
+fn total_best(likelihoods: &[[f64; ALPHABET_LEN]]) -> f64 {
+    likelihoods.iter().map(|by_shift| by_shift.iter().copied().fold(f64::MIN, f64::max)).sum()
+}
+
+// ways to rearrange an alphabet: swap two letters, or take one out and put it back elsewhere.
+// Keyword alphabets are mostly in order apart from the keyword, so moving a letter is often
+// the step a swap can't make without first making things worse
+#[derive(Debug, Clone, Copy)]
+enum Move {
+    Swap(usize, usize),
+    Insert(usize, usize),
+}
+
+impl Move {
+    fn inverse(self) -> Move {
+        match self {
+            Move::Swap(i, j) => Move::Swap(i, j),
+            Move::Insert(from, to) => Move::Insert(to, from),
+        }
+    }
+}
+
+// every swap and every insert that isn't the same as a swap of neighbours
+fn moves() -> Vec<Move> {
+    let mut moves = Vec::new();
+    for i in 0..ALPHABET_LEN {
+        for j in 0..ALPHABET_LEN {
+            if i < j {
+                moves.push(Move::Swap(i, j));
+            }
+            if i.abs_diff(j) > 1 {
+                moves.push(Move::Insert(i, j));
+            }
+        }
+    }
+    moves
+}
+
+fn rearrange(alphabet: &mut [u8; ALPHABET_LEN], mv: Move) {
+    match mv {
+        Move::Swap(i, j) => alphabet.swap(i, j),
+        Move::Insert(from, to) if from < to => alphabet[from..=to].rotate_left(1),
+        Move::Insert(from, to) => alphabet[to..=from].rotate_right(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encode::encode_quagmire;
+
+    // the opening of the Iliad followed by Hoare on software design
+    const PT: &str = "SINGOGODDESSTHEANGEROFACHILLESSONOFPELEUSTHATBROUGHTCOUNTLESSILLSUPONTHEACHAEANSMANYABRAVESOULDIDITSENDHURRYINGDOWNTOHADESANDMANYAHERODIDITYIELDAPREYTODOGSANDVULTURESFORSOWERETHECOUNSELSOFJOVEFULFILLEDFROMTHEDAYONWHICHTHESONOFATREUSKINGOFMENANDGREATACHILLESFIRSTFELLOUTWITHONEANOTHERTHEREARETWOWAYSOFCONSTRUCTINGASOFTWAREDESIGNONEWAYISTOMAKEITSOSIMPLETHATTHEREAREOBVIOUSLYNODEFICIENCIESANDTHEOTHERWAYISTOMAKEITSOCOMPLICATEDTHATTHEREARENOOBVIOUSDEFICIENCIESTHEFIRSTMETHODISFARMOREDIFFICULT";
+
+    #[test]
+    fn cracks_quagmire() {
+        let plaintext: String = PT.chars().take(600).collect();
+        for kind in [QuagmireKind::I, QuagmireKind::II] {
+            let quagmire = Quagmire::new(kind, "SPRINGFEVER", None, 'C').unwrap();
+            let mut keyphrase = KeyPhrase::new(String::from("KEY")).unwrap();
+            let ciphertext = encode_quagmire(&plaintext, &quagmire, &mut keyphrase).unwrap();
+            let solution = crack_quagmire(&ciphertext, &Ngram::english(), kind, None).unwrap();
+            assert_eq!(solution.plaintext, plaintext);
+            assert_eq!(solution.key.len(), 3);
+        }
+    }
+
+    /*
+        A whole crack of III or IV needs far more ciphertext than a test can afford to search (1400
+        letters under a 3 letter key isn't enough), so these start the n-gram climb a move away
+        from the real alphabets: one swap in both for III, which only a move applied to both at
+        once undoes, and one in each for IV, which takes moves in each alphabet in turn.
+    */
+    #[test]
+    fn climbs_quagmire_iii_and_iv() {
+        let ngram = Ngram::english();
+        let keyed = keyphrase::keyed_alphabet("SPRINGFEVER").unwrap();
+        for (kind, cipher_keyword) in [(QuagmireKind::III, None), (QuagmireKind::IV, Some("TEMPEST"))] {
+            let quagmire = Quagmire::new(kind, "SPRINGFEVER", cipher_keyword, 'C').unwrap();
+            let mut keyphrase = KeyPhrase::new(String::from("KEY")).unwrap();
+            let letters = to_letter_indices(&encode_quagmire(PT, &quagmire, &mut keyphrase).unwrap());
+            let (mut plain, mut cipher) = match cipher_keyword {
+                Some(keyword) => (keyed, keyphrase::keyed_alphabet(keyword).unwrap()),
+                None => (keyed, keyed),
+            };
+            plain.swap(3, 17);
+            match kind {
+                QuagmireKind::III => cipher.swap(3, 17),
+                _ => cipher.swap(5, 11),
+            }
+
+            let mut search = Search::new(&letters, 3, &ngram, kind);
+            search.pick_shifts(&plain, &cipher);
+            search.kicked_climb(&mut plain, &mut cipher, Fitness::Ngrams, NGRAM_PATIENCE);
+            search.decrypt(&plain, &cipher);
+            let plaintext: String = search.plaintext.iter().map(|&l| (l + b'A') as char).collect();
+            assert_eq!(plaintext, PT);
+            if kind == QuagmireKind::III {
+                // kicks and moves change both alphabets alike
+                assert_eq!(plain, cipher);
+            }
+        }
+    }
+
+    #[test]
+    fn crack_quagmire_errors() {
+        let ngram = Ngram::english();
+        assert_eq!(crack_quagmire("ABCDEFGH", &ngram, QuagmireKind::I, Some(0)), Err(VigenereError::ZeroKeyLength));
+        assert_eq!(crack_quagmire("AB", &ngram, QuagmireKind::I, Some(5)), Err(VigenereError::CiphertextTooShort { letters: 2, needed: 5 }));
+    }
+}
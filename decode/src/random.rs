@@ -0,0 +1,29 @@
+// xorshift, https://en.wikipedia.org/wiki/Xorshift
+// plenty for shaking up a search, and the same seed always gives the same numbers so cracks can be repeated
+pub(crate) struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    // a zero state would only ever give zeros, so seed 0 is swapped for another
+    pub(crate) fn new(seed: u64) -> Xorshift {
+        Xorshift { state: if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed } }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // uniform enough in 0..below for the small ranges a search needs
+    pub(crate) fn below(&mut self, below: usize) -> usize {
+        (self.next_u64() % below as u64) as usize
+    }
+
+    // in [0, 1), from the top 53 bits
+    pub(crate) fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,119 @@
+/*
+    Random restarts for the hill climb. Ngram::generate_key_from_parent stops at the first key no
+    single letter change improves, and which key that is depends on where it started. So besides
+    the usual start from the chi-squared key, the climb is run again from a number of random keys
+    and the best key any of them reaches is taken. How many climbs end on that same key says how
+    much to trust it: on plenty of ciphertext nearly every start leads there, while a best key
+    only one climb found may just be the least bad local optimum.
+
+    The random keys all come from one seeded generator, so a seed always gives the same climbs.
+
+    http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-vigenere-cipher-part-2/
+*/
+use decode_given_key::{decode_with_tableau, to_letter_indices, Ngram};
+use decode_given_length::find_key_with_shifts;
+use keyphrase::{group_ciphertext, Alphabet, KeyPhrase, Tableau, VigenereError, ALPHABET_LEN};
+
+use crate::determine_key_length_in;
+use crate::random::Xorshift;
+
+pub const DEFAULT_RESTARTS: usize = 20;
+pub const KEPT_RESULTS: usize = 5; // distinct keys kept, best first
+
+// a key some of the climbs ended on
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClimbResult {
+    pub key: String,
+    pub score: f64,       // Ngram::score_letters of the plaintext's letters
+    pub converged: usize, // climbs that ended on this key
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartSolution {
+    pub results: Vec<ClimbResult>, // at most KEPT_RESULTS, best first
+    pub climbs: usize,             // restarts plus the one from the chi-squared key
+    pub plaintext: String,         // with the best key
+}
+
+impl RestartSolution {
+    // fraction of all the climbs that found the best key
+    pub fn confidence(&self) -> f64 {
+        self.results[0].converged as f64 / self.climbs as f64
+    }
+}
+
+// cracks with the key length from determine_key_length_in, or key_length when given
+pub fn crack_with_restarts(ciphertext: &str, ngram: &Ngram, tableau: Tableau, key_length: Option<usize>, restarts: usize, seed: u64) -> Result<RestartSolution, VigenereError> {
+    let buckets = match key_length {
+        Some(0) => return Err(VigenereError::ZeroKeyLength),
+        Some(key_length) => group_ciphertext(&ciphertext.to_ascii_uppercase(), key_length),
+        None => determine_key_length_in(ciphertext, &Alphabet::letters(), ngram.language())?.1,
+    };
+    let mut stripped_ciphertext = String::from(ciphertext);
+    stripped_ciphertext.retain(|c| c.is_ascii_alphabetic());
+    stripped_ciphertext.make_ascii_uppercase();
+    if stripped_ciphertext.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: stripped_ciphertext.len(), needed: ngram.ngram_length() });
+    }
+
+    let parent = find_key_with_shifts(&buckets, tableau, ALPHABET_LEN as u8, ngram.language());
+    let mut results = climb_with_restarts(&stripped_ciphertext, &parent, ngram, tableau, restarts, seed);
+    results.truncate(KEPT_RESULTS);
+    let mut keyphrase = KeyPhrase::new(results[0].key.clone())?;
+    Ok(RestartSolution { plaintext: decode_with_tableau(ciphertext, &mut keyphrase, tableau)?, results, climbs: restarts + 1 })
+}
+
+// climbs from parent and then from restarts random keys as long as it, every key they end on best first
+pub fn climb_with_restarts(stripped_ciphertext: &str, parent: &str, ngram: &Ngram, tableau: Tableau, restarts: usize, seed: u64) -> Vec<ClimbResult> {
+    let letters = to_letter_indices(stripped_ciphertext);
+    let mut rng = Xorshift::new(seed);
+    let mut results: Vec<ClimbResult> = Vec::new();
+    let mut plaintext = vec![0; letters.len()];
+
+    for restart in 0..=restarts {
+        let start: String = match restart {
+            0 => String::from(parent),
+            _ => (0..parent.len()).map(|_| (b'A' + rng.below(ALPHABET_LEN) as u8) as char).collect(),
+        };
+        let key = ngram.generate_key_from_parent_with_tableau(start, String::from(stripped_ciphertext), tableau);
+        match results.iter_mut().find(|result| result.key == key) {
+            Some(result) => result.converged += 1,
+            None => {
+                let score = ngram.score_key(&to_letter_indices(&key), &letters, &mut plaintext, tableau);
+                results.push(ClimbResult { key, score, converged: 1 });
+            }
+        }
+    }
+    // stable sort, so of equal scores the key found first stays first
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decode_given_length::{SECRZUTY_CIPHERTEXT, SECRZUTY_PLAINTEXT};
+    use crate::DEFAULT_SEED;
+
+    #[test]
+    fn restarts_find_key() {
+        let ciphertext = SECRZUTY_CIPHERTEXT;
+        let ngram = Ngram::english();
+        let solution = crack_with_restarts(ciphertext, &ngram, Tableau::Vigenere, Some(8), DEFAULT_RESTARTS, DEFAULT_SEED).unwrap();
+        assert_eq!((solution.results[0].key.as_str(), solution.plaintext.as_str()), ("SECURITY", SECRZUTY_PLAINTEXT));
+        assert_eq!(solution.climbs, 21);
+        assert!(solution.results.len() <= KEPT_RESULTS && solution.results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        // 13 of the 21 climbs end on the key, 3 on the one the climb from the chi-squared key gets stuck on
+        let converged: Vec<(&str, usize)> = solution.results.iter().map(|result| (result.key.as_str(), result.converged)).collect();
+        assert_eq!(&converged[..2], [("SECURITY", 13), ("SECRZUTY", 3)]);
+        assert!(converged.iter().map(|&(_, count)| count).sum::<usize>() <= solution.climbs);
+        assert_eq!(solution.confidence(), 13.0 / 21.0);
+
+        // the same seed gives the same climbs, down to how many ended on each key
+        let repeat = crack_with_restarts(ciphertext, &ngram, Tableau::Vigenere, Some(8), DEFAULT_RESTARTS, DEFAULT_SEED).unwrap();
+        assert_eq!(repeat.results, solution.results);
+        assert_eq!(repeat, solution);
+        assert_eq!(crack_with_restarts(ciphertext, &ngram, Tableau::Vigenere, Some(0), 1, 1), Err(VigenereError::ZeroKeyLength));
+    }
+}
@@ -0,0 +1,76 @@
+/*
+    Running key analysis. When the key text is known or guessed (the book both sides use) but
+    not where in it the message starts, every starting offset can simply be tried: the right one
+    decrypts to English and the others to noise, which the n-gram model tells apart easily.
+    Only offsets where the rest of the key text is at least as long as the message are tried.
+
+    https://en.wikipedia.org/wiki/Running_key_cipher
+*/
+use decode_given_key::{decode_with_tableau, to_letter_indices, Ngram};
+use keyphrase::{RunningKey, Tableau, VigenereError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffsetScore {
+    pub offset: usize, // letters of the key text skipped before the key starts
+    pub score: f64,    // n-gram score of the plaintext this offset gives, higher is better
+}
+
+// scores every usable offset into key_text, best first
+pub fn search_offsets(ciphertext: &str, key_text: &str, ngram: &Ngram, tableau: Tableau) -> Result<Vec<OffsetScore>, VigenereError> {
+    let ciphertext = to_letter_indices(ciphertext);
+    let key = to_letter_indices(key_text);
+    if ciphertext.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: ciphertext.len(), needed: ngram.ngram_length() });
+    }
+    if key.len() < ciphertext.len() {
+        return Err(VigenereError::KeyTooShort { letters: key.len(), needed: ciphertext.len() });
+    }
+
+    // the same as Ngram::compute_score on each decryption, without building a String per offset
+    let mut plaintext = vec![0; ciphertext.len()];
+    let mut scores: Vec<OffsetScore> = key
+        .windows(ciphertext.len())
+        .enumerate()
+        .map(|(offset, key)| {
+            for ((p, &c), &k) in plaintext.iter_mut().zip(&ciphertext).zip(key) {
+                *p = tableau.decrypt(c, k);
+            }
+            OffsetScore { offset, score: ngram.score_letters(&plaintext) }
+        })
+        .collect();
+    // stable sort, so equal scores stay earliest offset first
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(scores)
+}
+
+// decrypts with the best offset from search_offsets, returning the offset and the plaintext
+pub fn crack_running_key(ciphertext: &str, key_text: &str, ngram: &Ngram, tableau: Tableau) -> Result<(usize, String), VigenereError> {
+    let offset = search_offsets(ciphertext, key_text, ngram, tableau)?[0].offset;
+    let mut running_key = RunningKey::new(key_text, offset)?;
+    Ok((offset, decode_with_tableau(ciphertext, &mut running_key, tableau)?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_TEXT: &str = "The fact that repeating letters can be found means two things: either a same sequence of letter of the plaintext is crypted with the same part of the key, either different sequences letters are crypted with different parts of the key but they ends with the same crypted letters. This second possibility is poorly probable.";
+
+    #[test]
+    fn finds_offset() {
+        let plaintext = "There are two ways of constructing a software design.";
+        let key = &to_letter_indices(KEY_TEXT)[37..];
+        let ciphertext: String = to_letter_indices(plaintext).iter().zip(key).map(|(&p, &k)| (Tableau::Vigenere.encrypt(p, k) + b'A') as char).collect();
+
+        let scores = search_offsets(&ciphertext, KEY_TEXT, &Ngram::english(), Tableau::Vigenere).unwrap();
+        assert_eq!(scores.len(), to_letter_indices(KEY_TEXT).len() - ciphertext.len() + 1);
+        assert_eq!(scores[0].offset, 37);
+
+        let (offset, decrypted) = crack_running_key(&ciphertext, KEY_TEXT, &Ngram::english(), Tableau::Vigenere).unwrap();
+        assert_eq!(offset, 37);
+        assert_eq!(decrypted, "THEREARETWOWAYSOFCONSTRUCTINGASOFTWAREDESIGN");
+
+        assert!(matches!(search_offsets(&ciphertext, "too short", &Ngram::english(), Tableau::Vigenere), Err(VigenereError::KeyTooShort { .. })));
+    }
+}
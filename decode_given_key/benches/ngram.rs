@@ -0,0 +1,153 @@
+// compares the dense table Ngram against the HashMap<String, f64> scorer it replaced
+// run with: cargo bench -p decode_given_key
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use decode_given_key::{decode, to_letter_indices, Ngram};
+use keyphrase::KeyPhrase;
+
+// the previous implementation, kept here only as the baseline to measure against
+struct HashMapNgram {
+    ngram_map: HashMap<String, f64>,
+    len: usize,
+    floor: f64,
+}
+
+impl HashMapNgram {
+    fn new(text: &str) -> HashMapNgram {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut total_chars: usize = 0;
+        let mut len = 0;
+        for line in text.lines() {
+            let mut split = line.split_ascii_whitespace();
+            let key = split.next().unwrap().to_string();
+            len = key.len();
+            let count: usize = split.next().unwrap().parse().unwrap();
+            total_chars += count;
+            counts.insert(key, count);
+        }
+
+        let ngram_map = counts
+            .into_iter()
+            .map(|(k, v)| (k, f64::log10(v as f64 / total_chars as f64)))
+            .collect();
+
+        HashMapNgram { ngram_map, len, floor: f64::log10(0.01 / total_chars as f64) }
+    }
+
+    fn compute_score(&self, ciphertext: &str) -> f64 {
+        let mut score = 0.0;
+        for i in 0..ciphertext.len() - self.len + 1 {
+            score += *self.ngram_map.get(&ciphertext[i..i + self.len]).unwrap_or(&self.floor);
+        }
+        score
+    }
+
+    fn generate_key_from_parent(&self, parent: String, stripped_ciphertext: &str) -> String {
+        let mut parent = parent.into_bytes();
+        let score_of = |key: &[u8]| {
+            let mut phrase = KeyPhrase::new(String::from_utf8(key.to_vec()).unwrap()).unwrap();
+            self.compute_score(&decode(stripped_ciphertext, &mut phrase))
+        };
+
+        let mut current_min_score = score_of(&parent);
+        loop {
+            let best_key_found = parent.clone();
+            for i in 0..parent.len() {
+                let mut current_min_char = parent[i];
+                for c in b'A'..=b'Z' {
+                    parent[i] = c;
+                    let score = score_of(&parent);
+                    if score > current_min_score {
+                        current_min_char = c;
+                        current_min_score = score;
+                    }
+                }
+                parent[i] = current_min_char;
+            }
+            if parent == best_key_found {
+                return String::from_utf8(parent).unwrap();
+            }
+        }
+    }
+}
+
+fn data(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(name);
+    fs::read_to_string(path).unwrap()
+}
+
+// runs f until at least min_time has passed and returns the mean time per run
+fn time<F: FnMut()>(min_time: Duration, mut f: F) -> Duration {
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < min_time {
+        f();
+        runs += 1;
+    }
+    start.elapsed() / runs
+}
+
+fn report(what: &str, old: Duration, new: Duration) {
+    println!(
+        "{:<48} hashmap {:>12.3?}   dense {:>12.3?}   {:>6.1}x",
+        what,
+        old,
+        new,
+        old.as_secs_f64() / new.as_secs_f64()
+    );
+}
+
+fn main() {
+    let hashmap_ngram = HashMapNgram::new(&data("src/english_quadgrams.txt"));
+    let dense_ngram = Ngram::english();
+
+    // (name, ciphertext, real key, starting key with some letters wrong like a chi-squared seed would have)
+    let cases = [
+        ("summer", data("benches/data/summer.txt"), String::from("SUMMER")),
+        (
+            "areyoujoking...",
+            data("benches/data/areyoujoking.txt"),
+            String::from("AREYOUJOKINGTHISISFREAKINGLONGCIPHERTEXTWHOTHEHELLISGOINGTODECRYPTITANYWAYLETSGOPLAYVIDEOGAMESANDBYE"),
+        ),
+    ];
+
+    for (name, ciphertext, key) in cases.iter() {
+        let mut stripped = ciphertext.clone();
+        stripped.retain(|c| c.is_ascii_alphabetic());
+        stripped.make_ascii_uppercase();
+
+        let mut phrase = KeyPhrase::new(key.clone()).unwrap();
+        let plaintext = decode(&stripped, &mut phrase);
+
+        // score an already decrypted text, converting it to letter indices as part of the dense timing
+        let old = time(Duration::from_secs(1), || {
+            std::hint::black_box(hashmap_ngram.compute_score(std::hint::black_box(&plaintext)));
+        });
+        let new = time(Duration::from_secs(1), || {
+            std::hint::black_box(dense_ngram.compute_score(std::hint::black_box(&plaintext)));
+        });
+        report(&format!("{} compute_score ({} letters)", name, stripped.len()), old, new);
+
+        let letters = to_letter_indices(&plaintext);
+        let new = time(Duration::from_secs(1), || {
+            std::hint::black_box(dense_ngram.score_letters(std::hint::black_box(&letters)));
+        });
+        report(&format!("{} score_letters", name), old, new);
+
+        // every 5th key letter starts as A
+        let seed: String = key.chars().enumerate().map(|(i, c)| if i % 5 == 0 { 'A' } else { c }).collect();
+        let mut old_key = String::new();
+        let mut new_key = String::new();
+        let old = time(Duration::ZERO, || {
+            old_key = hashmap_ngram.generate_key_from_parent(seed.clone(), &stripped);
+        });
+        let new = time(Duration::from_secs(1), || {
+            new_key = dense_ngram.generate_key_from_parent(seed.clone(), stripped.clone());
+        });
+        report(&format!("{} generate_key_from_parent", name), old, new);
+        assert_eq!(old_key, new_key, "both scorers should climb to the same key");
+    }
+}
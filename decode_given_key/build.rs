@@ -0,0 +1,40 @@
+// preprocesses each src/<language>_quadgrams.txt into a dense table of little-endian u32 counts, one
+// per quadgram in AAAA..ZZZZ order, so the built-in models are compiled into the crate with
+// include_bytes! instead of being opened relative to whatever directory the program is run from
+use std::{env, fs, path::Path};
+
+const QUADGRAM_LEN: u32 = 4;
+const MODELS: [&str; 6] = ["english", "german", "french", "spanish", "italian", "portuguese"];
+
+fn main() {
+    for model in MODELS {
+        preprocess(&format!("{}_quadgrams", model));
+    }
+}
+
+fn preprocess(name: &str) {
+    let src = Path::new("src").join(format!("{}.txt", name));
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let text = fs::read_to_string(&src).unwrap_or_else(|_| panic!("could not read {}", src.display()));
+    let mut table = vec![0u32; 26usize.pow(QUADGRAM_LEN)];
+
+    for (n, line) in text.lines().enumerate() {
+        let mut split = line.split_ascii_whitespace();
+        let (gram, count) = match (split.next(), split.next()) {
+            (Some(gram), Some(count)) => (gram, count),
+            _ => panic!("{}.txt line {}: expected a quadgram and a count", name, n + 1),
+        };
+        if gram.len() != QUADGRAM_LEN as usize || !gram.bytes().all(|b| b.is_ascii_uppercase()) {
+            panic!("{}.txt line {}: {:?} is not a quadgram", name, n + 1, gram);
+        }
+        let count: u32 = count.parse().unwrap_or_else(|_| panic!("{}.txt line {}: bad count", name, n + 1));
+
+        let idx = gram.bytes().fold(0usize, |idx, b| idx * 26 + (b - b'A') as usize);
+        table[idx] = count;
+    }
+
+    let bytes: Vec<u8> = table.iter().flat_map(|count| count.to_le_bytes()).collect();
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join(format!("{}.bin", name));
+    fs::write(out, bytes).unwrap_or_else(|_| panic!("could not write {}.bin", name));
+}
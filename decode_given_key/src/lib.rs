@@ -1,44 +1,83 @@
-use keyphrase::KeyPhrase;
-use std::{collections::HashMap, fs::File,};
+use keyphrase::{Alphabet, KeyPhrase, KeyStream, Language, PartialKey, Quagmire, Tableau, VigenereError, ALPHABET_LEN};
+use std::{fs::File, path::Path};
 use std::io::{BufReader, BufRead};
 const ASCII_UPPER_OFFSET: u8 = 65;
-const ASCII_LOWER_OFFSET: u8 = 97; 
-const KEYPHRASE_LEN: u8 = 26; 
+
+// <language>_quadgrams.txt preprocessed by build.rs: a little-endian u32 count for every quadgram AAAA..ZZZZ
+const ENGLISH_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/english_quadgrams.bin"));
+const GERMAN_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/german_quadgrams.bin"));
+const FRENCH_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/french_quadgrams.bin"));
+const SPANISH_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/spanish_quadgrams.bin"));
+const ITALIAN_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/italian_quadgrams.bin"));
+const PORTUGUESE_QUADGRAMS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/portuguese_quadgrams.bin"));
+const QUADGRAM_LEN: usize = 4;
+
+// what decode_partial writes for a letter whose key letter isn't known
+pub const MASK: char = '_';
 
 pub fn decode(ciphertext: &str, keyphrase: &mut KeyPhrase) -> String {
+    decode_with_tableau(ciphertext, keyphrase, Tableau::Vigenere).expect("a repeating key never runs out")
+}
+
+// same as decode for text encrypted with encode::encode_with_tableau, with any key stream
+pub fn decode_with_tableau<K: KeyStream + ?Sized>(ciphertext: &str, key: &mut K, tableau: Tableau) -> Result<String, VigenereError> {
+    decode_in_alphabet(ciphertext, key, tableau, &Alphabet::letters())
+}
+
+
+// reverses encode::encode_in_alphabet
+pub fn decode_in_alphabet<K: KeyStream + ?Sized>(ciphertext: &str, key: &mut K, tableau: Tableau, alphabet: &Alphabet) -> Result<String, VigenereError> {
+    if !tableau.supports(alphabet.len()) {
+        return Err(VigenereError::UnsupportedTableau(tableau));
+    }
+    decode_symbols(ciphertext, key, alphabet, |cipher, shift| tableau.decrypt_in(cipher, shift, alphabet.len()))
+}
+
+
+// decode with the known letters of a repeating key, letters under an unknown one come out as MASK
+pub fn decode_partial(ciphertext: &str, key: &PartialKey, tableau: Tableau) -> String {
+    let alphabet = Alphabet::letters();
+    let mut i = 0;
+    ciphertext
+        .chars()
+        .map(|c| match alphabet.index_of(c) {
+            Some(cipher) => {
+                let shift = key.shift_at(i);
+                i += 1;
+                match shift {
+                    Some(shift) => alphabet.symbol_like(tableau.decrypt(cipher, shift), c),
+                    None => MASK,
+                }
+            }
+            None => c,
+        })
+        .collect()
+}
+
+
+// reverses encode::encode_quagmire given the same alphabets, indicator and key
+pub fn decode_quagmire<K: KeyStream + ?Sized>(ciphertext: &str, quagmire: &Quagmire, key: &mut K) -> Result<String, VigenereError> {
+    decode_symbols(ciphertext, key, &Alphabet::letters(), |cipher, shift| quagmire.decrypt(cipher, shift))
+}
+
+
+// decrypts each symbol with the next shift and hands the result back to the key, which an autokey
+// needs before it can give the shift a primer length later
+fn decode_symbols<K: KeyStream + ?Sized>(ciphertext: &str, key: &mut K, alphabet: &Alphabet, decrypt: impl Fn(u8, u8) -> u8) -> Result<String, VigenereError> {
     let mut plaintext = String::with_capacity(ciphertext.len());
     for c in ciphertext.chars() {
-        if c.is_ascii_lowercase() {
-            let offset = keyphrase.give_next_offset();
-            let c_numeric = c as u8 - ASCII_LOWER_OFFSET; 
-
-            if offset > c_numeric {
-                let new_char = (KEYPHRASE_LEN - offset + c_numeric + ASCII_LOWER_OFFSET) as char; // 26 - 20 + 6 = 12
-                plaintext.push(new_char);
-                
-            }
-            else {
-                let new_char = (((c_numeric - offset) % KEYPHRASE_LEN) + ASCII_LOWER_OFFSET) as char;
-                plaintext.push(new_char);
+        let cipher = match alphabet.index_of(c) {
+            Some(cipher) => cipher,
+            None => {
+                plaintext.push(c);
+                continue;
             }
-        }
-        else if c.is_ascii_uppercase() {
-            let offset = keyphrase.give_next_offset();
-            let c_numeric = c as u8 - ASCII_UPPER_OFFSET; 
-            if offset > c_numeric {
-                let new_char = (KEYPHRASE_LEN - offset + c_numeric + ASCII_UPPER_OFFSET) as char; 
-                plaintext.push(new_char);
-            }
-            else {
-                let new_char = (((c_numeric - offset) % KEYPHRASE_LEN) + ASCII_UPPER_OFFSET) as char;
-                plaintext.push(new_char);
-            } 
-        }
-        else {
-            plaintext.push(c);
-        }
+        };
+        let plain = decrypt(cipher, key.next_shift()?);
+        key.push_plaintext(plain);
+        plaintext.push(alphabet.symbol_like(plain, c));
     }
-     plaintext
+    Ok(plaintext)
 }
 
 
@@ -47,124 +86,367 @@ pub fn decode(ciphertext: &str, keyphrase: &mut KeyPhrase) -> String {
     // http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-vigenere-cipher-part-2/
     // http://practicalcryptography.com/media/cryptanalysis/files/ngram_score_1.py
     // CITED MULTIPLE TIMES BECAUSE THIS CODE IS THE MOST SIMILAR SNIPPETS I USED
+    //
+    // the log probabilities live in a flat table with one entry for every possible ngram, indexed by
+    // reading the ngram as a base-26 number (AAAA = 0, AAAB = 1, ..., ZZZZ = 26^4 - 1). ngrams that never
+    // appeared hold the floor, so scoring is one array lookup per position with no hashing or allocation
 pub struct Ngram {
-    ngram_map: HashMap<String, f64>,
+    table: Vec<f32>,
     len: usize,
-    floor: f64,
+    language: Language, // what the model is of, for the crackers that also use letter frequencies and IOC
+    random: f64,        // normalised_score's 0.0, the mean floored log frequency per n-gram of uniformly random letters
+    expected: f64,      // normalised_score's 1.0, the mean floored log frequency per n-gram of the model's own text
+}
+
+// normalised_score counts any n-gram rarer than one in a million as that rare, about where the
+// smallest built-in corpus runs out of counts, so the size of a model's corpus doesn't move its scale
+const NORMALISED_FLOOR: f64 = -6.0;
+
+const MAX_NGRAM_LEN: usize = 5; // the table has 26^len entries, 26^5 floats is already ~47MB
+
+// converts the letters of text to 0..=25 (case insensitive) and drops everything else,
+// done once up front so the text can be scored many times without touching strings
+pub fn to_letter_indices(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase() - ASCII_UPPER_OFFSET)
+        .collect()
 }
+
 impl Ngram {
 
+    // the English quadgram model compiled into the crate, works from any directory
+    pub fn english() -> Ngram {
+        Ngram::from_table(ENGLISH_QUADGRAMS)
+    }
+
+    /*
+        The quadgram model built in for language, None for Russian, whose letters the models can't
+        score. The German, French, Spanish, Italian and Portuguese counts are of the translated
+        messages and manual pages that ship with Debian, folded to A-Z the way the letter
+        frequencies in keyphrase::Language are (accents dropped, ß as SS), so they lean towards
+        words about files and options but are still far better than single letter frequencies.
+    */
+    pub fn for_language(language: Language) -> Option<Ngram> {
+        let table = match language {
+            Language::English => ENGLISH_QUADGRAMS,
+            Language::German => GERMAN_QUADGRAMS,
+            Language::French => FRENCH_QUADGRAMS,
+            Language::Spanish => SPANISH_QUADGRAMS,
+            Language::Italian => ITALIAN_QUADGRAMS,
+            Language::Portuguese => PORTUGUESE_QUADGRAMS,
+            Language::Russian => return None,
+        };
+        Some(Ngram::from_table(table).with_language(language))
+    }
+
+    fn from_table(table: &[u8]) -> Ngram {
+        let counts: Vec<usize> = table
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+            .collect();
+        Ngram::from_counts(&counts, QUADGRAM_LEN)
+    }
+
+    // a custom model in the same "NGRAM COUNT" per line format as english_quadgrams.txt
+    pub fn from_path(path: &Path) -> Result<Ngram, VigenereError> {
+        let file = File::open(path).map_err(|e| VigenereError::MissingModel(format!("{}: {}", path.display(), e)))?;
+        Ngram::new(file)
+    }
+
     // http://practicalcryptography.com/media/cryptanalysis/files/ngram_score_1.py
-    pub fn new(file: File) -> Ngram {
-        let mut counts: HashMap<String,usize> = HashMap::new();
+    pub fn new(file: File) -> Result<Ngram, VigenereError> {
+        let mut counts: Vec<usize> = Vec::new();
         let mut buf = String::with_capacity(100);
-        let mut total_chars: usize = 0;
         let mut len = 0;
+        let mut line = 0;
 
         let mut reader = BufReader::new(file);
-        while let Ok(bytes) = reader.read_line(&mut buf) {
+        loop {
+            let bytes = reader.read_line(&mut buf).map_err(|e| VigenereError::MissingModel(e.to_string()))?;
             if bytes == 0 {
                 break;
             }
-            buf.pop(); // pop LF
+            line += 1;
+            let corrupt = |reason: &str| VigenereError::CorruptModel { line, reason: reason.to_string() };
+
             let mut split = buf.split_ascii_whitespace();
-            let key = split.next().unwrap().to_string();
-            len = key.len();
-            let count: usize = split.next().unwrap().parse().unwrap();
-            total_chars += count;
+            let key = match split.next() {
+                Some(key) => key,
+                None => return Err(corrupt("expected an n-gram and a count")),
+            };
+            if !key.bytes().all(|b| b.is_ascii_uppercase()) {
+                return Err(corrupt("n-grams may only contain letters A-Z"));
+            }
+            if len == 0 {
+                len = key.len();
+                if len > MAX_NGRAM_LEN {
+                    return Err(corrupt("n-grams longer than 5 letters are not supported"));
+                }
+                counts = vec![0; 26usize.pow(len as u32)];
+            }
+            else if key.len() != len {
+                return Err(corrupt("n-grams must all have the same length"));
+            }
+            let count: usize = match split.next().map(str::parse) {
+                Some(Ok(count)) => count,
+                _ => return Err(corrupt("expected a count after the n-gram")),
+            };
 
-            counts.insert(key, count);
+            let idx = to_letter_indices(key).iter().fold(0, |idx, &l| idx * 26 + l as usize);
+            counts[idx] += count;
 
             buf.clear();
         }
+        if counts.iter().all(|&count| count == 0) {
+            return Err(VigenereError::CorruptModel { line, reason: String::from("the model contains no n-grams") });
+        }
+        Ok(Ngram::from_counts(&counts, len))
+    }
 
-        let mut ngram_map: HashMap<String, f64> = HashMap::new();
+    // counts[idx] is how often the ngram with base-26 index idx was seen
+    fn from_counts(counts: &[usize], len: usize) -> Ngram {
+        let total_chars: usize = counts.iter().sum();
+        let floor = f64::log10(0.01/total_chars as f64);
 
-        for (k,v) in counts {
-            ngram_map.insert(k, f64::log10( v as f64 / total_chars as f64));
-            // map an ngram to the log of its frequency
-        }
+        // map an ngram to the log of its frequency
+        let table = counts
+            .iter()
+            .map(|&count| if count == 0 { floor } else { f64::log10(count as f64 / total_chars as f64) } as f32)
+            .collect::<Vec<f32>>();
+        let random = table.iter().map(|&log| (log as f64).max(NORMALISED_FLOOR)).sum::<f64>() / table.len() as f64;
+        let expected = table.iter().map(|&log| 10f64.powf(log as f64) * (log as f64).max(NORMALISED_FLOOR)).sum();
 
         Ngram {
-            ngram_map,
+            table,
             len,
-            floor: f64::log10(0.01/total_chars as f64)
+            language: Language::English,
+            random,
+            expected,
         }
     }
 
+    // marks a model loaded from a file as being of another language than English
+    pub fn with_language(mut self, language: Language) -> Ngram {
+        self.language = language;
+        self
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    // length of the n-grams in the model, e.g. 4 for quadgrams
+    pub fn ngram_length(&self) -> usize {
+        self.len
+    }
+
     // http://practicalcryptography.com/media/cryptanalysis/files/ngram_score_1.py
     pub fn compute_score(&self, ciphertext: &str) -> f64 {
-        let mut score = 0.0;
+        self.score_letters(&to_letter_indices(ciphertext))
+    }
 
-        for i in 0..ciphertext.len() - self.len + 1 {
-            if let Some(prob) = self.ngram_map.get(&ciphertext[i..i+self.len]) {
-                score += *prob;
-            }
-            else {
-                score += self.floor;
-            }
+    // same as compute_score for text already converted with to_letter_indices
+    pub fn score_letters(&self, letters: &[u8]) -> f64 {
+        // texts shorter than one n-gram have nothing to score
+        if letters.len() < self.len {
+            return 0.0;
+        }
+
+        // slide a window over the text, shifting the oldest letter out of the index as a new one comes in
+        let modulus = self.table.len();
+        let mut idx = letters[..self.len - 1].iter().fold(0, |idx, &l| idx * 26 + l as usize);
+        let mut score = 0.0;
+        for &l in &letters[self.len - 1..] {
+            idx = (idx * 26 + l as usize) % modulus;
+            score += self.table[idx] as f64;
         }
         score
     }
 
-    pub fn generate_key_from_parent(&self, mut parent: String, stripped_ciphertext: String) -> String {
-
-        //let original_parent = parent.as_str().clone().as_bytes();
-        let mut phrase = KeyPhrase::new(String::from(parent.as_str())).unwrap();
-
-        let plaintext = decode(&stripped_ciphertext, &mut phrase);
-        let parent_score = self.compute_score(&plaintext);
-        let mut current_min_score = parent_score;
-
-        let mut better_key_found = false;
-        let mut better_letter_found = false; 
+    /*
+        compute_score per n-gram, scaled so uniformly random letters come out at 0.0 and text whose
+        n-grams turn up as often as in the model's own counts at 1.0, with every log frequency
+        raised to NORMALISED_FLOOR. Raw scores depend on the n-gram length and on how big a corpus
+        the model was counted from, as that sets how low its floor is, so only this can be
+        compared between models of the same length, e.g. to tell which language a plaintext is in.
+    */
+    pub fn normalised_score(&self, text: &str) -> f64 {
+        let letters = to_letter_indices(text);
+        if letters.len() < self.len {
+            return 0.0;
+        }
+        let logs = letters.windows(self.len).map(|ngram| {
+            let idx = ngram.iter().fold(0, |idx, &l| idx * 26 + l as usize);
+            (self.table[idx] as f64).max(NORMALISED_FLOOR)
+        });
+        let per_ngram = logs.sum::<f64>() / (letters.len() - self.len + 1) as f64;
+        // a flat model, every n-gram as likely, has nothing to scale by
+        if self.expected <= self.random {
+            return 0.0;
+        }
+        (per_ngram - self.random) / (self.expected - self.random)
+    }
 
-        let len = parent.len();
+    pub fn generate_key_from_parent(&self, parent: String, stripped_ciphertext: String) -> String {
+        self.generate_key_from_parent_with_tableau(parent, stripped_ciphertext, Tableau::Vigenere)
+    }
 
-        loop {
-            let best_key_found = String::from(parent.as_str());
-            for i in 0..len {
+    // same hill climb for ciphertext encrypted with any tableau, only the decryption step differs
+    pub fn generate_key_from_parent_with_tableau(&self, parent: String, stripped_ciphertext: String, tableau: Tableau) -> String {
+        self.generate_key_from_parent_with_shifts(parent, stripped_ciphertext, tableau, ALPHABET_LEN as u8)
+    }
 
-                let mut current_min_char: u8 = 0;
-                let original_char: u8 = *parent.as_bytes().get(i).unwrap();
-                for j in 0..26 {
-                    let c = 65 + j;
+    // only tries the first `shifts` letters in each position, e.g. A-J for a Gronsfeld key
+    pub fn generate_key_from_parent_with_shifts(&self, parent: String, stripped_ciphertext: String, tableau: Tableau, shifts: u8) -> String {
+        let key = to_letter_indices(&parent);
+        if key.is_empty() {
+            return parent;
+        }
+        let locked = vec![false; key.len()];
+        self.climb(key, &to_letter_indices(&stripped_ciphertext), tableau, shifts, &locked)
+    }
 
-                    unsafe{
-                        let bytes = parent.as_bytes_mut();
-                        bytes[i] = c; // change the i'th character of parent key to variable c
-                    }
+    // the hill climb with the letters known holds fixed, only the rest of parent (as long as known) is changed.
+    // letters of a longer parent past the end of known are changed too
+    pub fn generate_key_from_parent_locked(&self, parent: String, stripped_ciphertext: String, tableau: Tableau, shifts: u8, known: &PartialKey) -> String {
+        let mut key = to_letter_indices(&parent);
+        let locked: Vec<bool> = (0..key.len()).map(|i| known.shifts().get(i).is_some_and(|shift| shift.is_some())).collect();
+        for (letter, shift) in key.iter_mut().zip(known.shifts()) {
+            if let Some(shift) = shift {
+                *letter = *shift;
+            }
+        }
+        self.climb(key, &to_letter_indices(&stripped_ciphertext), tableau, shifts, &locked)
+    }
 
-                    let mut phrase = KeyPhrase::new(String::from(parent.as_str())).unwrap();
+    // changes one unlocked letter of key at a time to whichever of the first `shifts` scores best, until none improves
+    fn climb(&self, mut key: Vec<u8>, ciphertext: &[u8], tableau: Tableau, shifts: u8, locked: &[bool]) -> String {
+        let mut plaintext = vec![0; ciphertext.len()];
 
-                    let plaintext = decode(&stripped_ciphertext, &mut phrase);
+        let mut current_min_score = self.score_key(&key, ciphertext, &mut plaintext, tableau);
 
-                    let score = self.compute_score(&plaintext);
+        loop {
+            let best_key_found = key.clone();
+            for i in (0..key.len()).filter(|&i| !locked[i]) {
+                // try every letter in the i'th position and keep whichever beats the best score so far
+                let mut current_min_char = key[i];
+                for j in 0..shifts {
+                    key[i] = j;
+                    let score = self.score_key(&key, ciphertext, &mut plaintext, tableau);
 
                     if score > current_min_score {
-                        better_key_found = true;
-                        better_letter_found = true;
-                        current_min_char = c;
+                        current_min_char = j;
                         current_min_score = score;
                     }
                 }
-                if better_letter_found {
-                    unsafe{
-                        let bytes = parent.as_bytes_mut();
-                        bytes[i] = current_min_char; // if a better letter fits for the key, change the key to have that letter
-                    }
-                    better_letter_found = false;
-                }
-                else {
-                    unsafe {
-                        let bytes = parent.as_bytes_mut();
-                        bytes[i] = original_char;
-                    }
-                }
+                key[i] = current_min_char;
             }
-            if !better_key_found || parent.eq(&best_key_found) {
-                return parent;
+            if key == best_key_found {
+                return key.iter().map(|&l| (l + ASCII_UPPER_OFFSET) as char).collect();
             }
         }
     }
+
+    // decrypts the ciphertext letters with key into plaintext and scores the result
+    pub fn score_key(&self, key: &[u8], ciphertext: &[u8], plaintext: &mut [u8], tableau: Tableau) -> f64 {
+        for (i, (p, c)) in plaintext.iter_mut().zip(ciphertext).enumerate() {
+            *p = tableau.decrypt(*c, key[i % key.len()]);
+        }
+        self.score_letters(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyphrase::{AutoKey, QuagmireKind, RunningKey};
+    use std::path::PathBuf;
+
+    #[test]
+    fn english_model_matches_text_file() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/english_quadgrams.txt");
+        let from_file = Ngram::from_path(&path).unwrap();
+        let english = Ngram::english();
+        assert_eq!(english.ngram_length(), 4);
+        assert_eq!(from_file.compute_score("HELLOWORLDTHISISATEST"), english.compute_score("HELLOWORLDTHISISATEST"));
+        assert_eq!(from_file.compute_score("QXZJQXZJ"), english.compute_score("QXZJQXZJ"));
+    }
+
+    #[test]
+    fn language_models() {
+        for language in Language::ALL.into_iter().filter(|language| language.is_latin()) {
+            let ngram = Ngram::for_language(language).unwrap();
+            assert_eq!((ngram.ngram_length(), ngram.language()), (QUADGRAM_LEN, language));
+        }
+        assert!(Ngram::for_language(Language::Russian).is_none());
+
+        // the same letters in another order have the same frequencies, only the quadgrams tell them apart
+        let german = to_letter_indices("Am Rande des kleinen Dorfes stand eine alte Muehle, deren Rad sich schon lange nicht mehr drehte.");
+        let shuffled: Vec<u8> = (0..german.len()).map(|i| german[i * 7 % german.len()]).collect();
+        let ngram = Ngram::for_language(Language::German).unwrap();
+        assert!(ngram.score_letters(&german) > ngram.score_letters(&shuffled));
+        let text = "Die Kinder erzaehlten sich, dass dort nachts ein Licht brenne und jemand leise singe.";
+        assert!(ngram.normalised_score(text) > Ngram::english().normalised_score(text));
+    }
+
+    #[test]
+    fn missing_or_corrupt_model() {
+        let missing = Ngram::from_path(&PathBuf::from("no/such/model.txt"));
+        assert!(matches!(missing, Err(VigenereError::MissingModel(_))));
+
+        let this_file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs");
+        assert!(matches!(Ngram::from_path(&this_file), Err(VigenereError::CorruptModel { line: 1, .. })));
+    }
+
+    #[test]
+    fn decode1() {
+        let ciphertext = "Zinff ehpdh123!";
+        let phrase = String::from("SECURITY");
+        let mut keyphrase: KeyPhrase = KeyPhrase::new(phrase).unwrap();
+        assert_eq!("Hello world123!", decode(ciphertext, &mut keyphrase));
+    }
+
+    #[test]
+    fn decode2() {
+        let ciphertext = "zinf-f ehp dh!";
+        let phrase = String::from("SECURITY");
+        let mut keyphrase: KeyPhrase  = KeyPhrase::new(phrase).unwrap();
+        assert_eq!("hell-o wor ld!", decode(ciphertext, &mut keyphrase));
+    }
+
+    #[test]
+    fn decode_beaufort() {
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        assert_eq!(Ok(String::from("Hello world123!")), decode_with_tableau("Larjd mfhhb123!", &mut keyphrase, Tableau::Beaufort));
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        assert_eq!(Ok(String::from("Hello world123!")), decode_with_tableau("Pajrx ovttz123!", &mut keyphrase, Tableau::VariantBeaufort));
+    }
+
+    #[test]
+    fn decode_autokey_works() {
+        let mut autokey = AutoKey::new(String::from("QUEENLY")).unwrap();
+        assert_eq!(Ok(String::from("Attack at dawn!")), decode_with_tableau("Qnxepv yt wtwp!", &mut autokey, Tableau::Vigenere));
+    }
+
+    #[test]
+    fn decode_partial_works() {
+        let key = PartialKey::new("SEC?R?TY").unwrap();
+        assert_eq!("Hel_o _orld123!", decode_partial("Zinff ehpdh123!", &key, Tableau::Vigenere));
+    }
+
+    #[test]
+    fn decode_running_key_works() {
+        let mut running_key = RunningKey::new("Ye security, secure!", 2).unwrap();
+        assert_eq!(Ok(String::from("Hello world123!")), decode_with_tableau("Zinff ehpdh123!", &mut running_key, Tableau::Vigenere));
+    }
+
+    #[test]
+    fn decode_quagmire_works() {
+        for (kind, ciphertext) in [(QuagmireKind::I, "Zimep yrsch123!"), (QuagmireKind::II, "Giqsi emnlh123!"), (QuagmireKind::III, "Ginoz oyvjh123!")] {
+            let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+            let quagmire = Quagmire::new(kind, "KRYPTOS", None, 'A').unwrap();
+            assert_eq!(Ok(String::from("Hello world123!")), decode_quagmire(ciphertext, &quagmire, &mut keyphrase));
+        }
+    }
 }
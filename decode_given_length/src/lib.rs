@@ -1,98 +1,198 @@
-use std::{collections::HashMap, fs::File,};
-use keyphrase::{KeyPhrase, calculate_chi_squared};
-use decode_given_key::{decode, Ngram};
+use std::collections::HashMap;
+use keyphrase::{Alphabet, KeyPhrase, Language, PartialKey, Tableau, VigenereError, calculate_chi_squared_for, calculate_chi_squared_in, ALPHABET_LEN, GRONSFELD_SHIFTS};
+use decode_given_key::{decode_in_alphabet, decode_with_tableau, Ngram};
 
+// a ciphertext under key SECURITY too short for the hill climb from the chi-squared key, which
+// settles on SECRZUTY, for testing the crackers meant to do better here and in decode
+pub const SECRZUTY_CIPHERTEXT: &str = "Eign dm uw llg icl vfmveb rnmcj xjy dikiwx effaxq lspcxpm";
+pub const SECRZUTY_PLAINTEXT: &str = "Meet me by the old church after the market closes tonight";
 
+pub fn decode_given_length(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>) -> Result<(String, String), VigenereError> {
+    // citing here again for what an ngram is and what source code I used.
+    //  http://practicalcryptography.com/media/cryptanalysis/files/ngram_score_1.py
+    //  http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-vigenere-cipher-part-2/
+    decode_given_length_with_model(ciphertext, key_length, buckets, &Ngram::english(), Tableau::Vigenere)
+}
+
+// same as decode_given_length but refines the key with a caller supplied model, e.g. from Ngram::from_path,
+// for ciphertext encrypted with any tableau
+pub fn decode_given_length_with_model(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>, ngram: &Ngram, tableau: Tableau) -> Result<(String, String), VigenereError> {
+    decode_given_length_with_shifts(ciphertext, key_length, buckets, ngram, tableau, ALPHABET_LEN as u8)
+}
+
+/*
+    Gronsfeld version of decode_given_length_with_model. Searching only the 10 shifts a digit can
+    give is faster, and on short texts it stops a column settling on a letter no Gronsfeld key
+    could have. The key comes back as digits.
+*/
+pub fn decode_given_length_gronsfeld(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>, ngram: &Ngram, tableau: Tableau) -> Result<(String, String), VigenereError> {
+    let (key, plaintext) = decode_given_length_with_shifts(ciphertext, key_length, buckets, ngram, tableau, GRONSFELD_SHIFTS)?;
+    Ok((key.bytes().map(|b| (b - b'A' + b'0') as char).collect(), plaintext))
+}
+
+/*
+    decode_given_length_with_model for a key partly known already, e.g. SEC?R?TY worked out by
+    hand or from decode::crib::drag_crib. The key length is known's, its letters are kept as they
+    are and only the unknown ones are searched for, first by chi-squared and then by the hill climb.
+    buckets must be grouped for known's length, anything else is a KeyLengthMismatch.
+*/
+pub fn decode_given_partial_key(ciphertext: &str, known: &PartialKey, buckets: Vec<HashMap<char, usize>>, ngram: &Ngram, tableau: Tableau) -> Result<(String, String), VigenereError> {
+    decode_given_known_with_shifts(ciphertext, known, buckets, ngram, tableau, ALPHABET_LEN as u8)
+}
 
-pub fn decode_given_length(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>) -> (String, String) {
+/*
+    decode_given_length for a cipher over another alphabet, e.g. A-Z0-9, with buckets from
+    keyphrase::group_ciphertext_in. The n-gram model only knows letters, so each column's key
+    symbol comes from chi-squared alone, against language's letter frequencies, which wants a few
+    dozen symbols per column to be right.
+*/
+pub fn decode_given_length_in(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>, tableau: Tableau, alphabet: &Alphabet, language: Language) -> Result<(String, String), VigenereError> {
     if key_length == 0 {
-        return (String::new(), ciphertext.to_string());
+        return Err(VigenereError::ZeroKeyLength);
+    }
+    if !tableau.supports(alphabet.len()) {
+        return Err(VigenereError::UnsupportedTableau(tableau));
     }
-    let new_key = find_key(&buckets);
+    let key = find_key_in_alphabet(&buckets, tableau, alphabet, language);
+    let mut phrase = KeyPhrase::in_alphabet(&key, alphabet)?;
+    let plaintext = decode_in_alphabet(ciphertext, &mut phrase, tableau, alphabet)?;
+    Ok((key, plaintext))
+}
 
-    
-    // specify absolue path: /home/jwaibong/cse360/hw1/decode_given_length/src/english_quadgrams.txt
-    // when running cargo test
-    let f = File::open("decode_given_length/src/english_quadgrams.txt").unwrap();
-    
+fn decode_given_length_with_shifts(ciphertext: &str, key_length: usize, buckets: Vec<HashMap<char, usize>>, ngram: &Ngram, tableau: Tableau, shifts: u8) -> Result<(String, String), VigenereError> {
+    // nothing known, so every letter is searched for
+    let known = PartialKey::unknown(key_length)?;
+    decode_given_known_with_shifts(ciphertext, &known, buckets, ngram, tableau, shifts)
+}
+
+fn decode_given_known_with_shifts(ciphertext: &str, known: &PartialKey, buckets: Vec<HashMap<char, usize>>, ngram: &Ngram, tableau: Tableau, shifts: u8) -> Result<(String, String), VigenereError> {
+    // the key is as long as buckets, which the hill climb expects known to match
+    if buckets.len() != known.len() {
+        return Err(VigenereError::KeyLengthMismatch { key: known.len(), columns: buckets.len() });
+    }
 
-    // citing here again for what an ngram is and what source code I used.
-    //  http://practicalcryptography.com/media/cryptanalysis/files/ngram_score_1.py
-    //  http://practicalcryptography.com/cryptanalysis/stochastic-searching/cryptanalysis-vigenere-cipher-part-2/
-    let ngram = Ngram::new(f);
-    
     let mut stripped_ciphertext: String = String::from(ciphertext);
-    stripped_ciphertext.retain(|c| c.is_alphabetic());
+    stripped_ciphertext.retain(|c| c.is_ascii_alphabetic());
     stripped_ciphertext.make_ascii_uppercase();
 
-    let new_key = ngram.generate_key_from_parent(new_key, stripped_ciphertext);
-    
+    // need at least one full n-gram of plaintext to score a key
+    if stripped_ciphertext.len() < ngram.ngram_length() {
+        return Err(VigenereError::CiphertextTooShort { letters: stripped_ciphertext.len(), needed: ngram.ngram_length() });
+    }
+
+    let new_key = find_key_locked(&buckets, known, tableau, shifts, ngram.language());
+    let new_key = ngram.generate_key_from_parent_locked(new_key, stripped_ciphertext, tableau, shifts, known);
+
     let copy_key = String::from(new_key.as_str());
-    let mut phrase = KeyPhrase::new(new_key).unwrap();
-    return (copy_key, decode(ciphertext, &mut phrase));
+    let mut phrase = KeyPhrase::new(new_key)?;
+    Ok((copy_key, decode_with_tableau(ciphertext, &mut phrase, tableau)?))
 }
 
 
 // http://practicalcryptography.com/cryptanalysis/text-characterisation/chi-squared-statistic/
-pub fn find_key(buckets: &Vec<HashMap<char, usize>>) -> String {
-    let mut key = String::with_capacity(100); // something default
-    
-
-    for (_, bucket) in buckets.iter().enumerate() { // buckets is our grouping based on key length
-        let mut min: f64 = f64::MAX;
-        let mut idx: i32 = -1;
-        for j in 0..26 { // for each bucket, we need to test 26 caesar ciphers and pick the most likely one based on chi-squared test
-
-            let mut new_bucket = String::with_capacity(100);
-            for (ch, count) in bucket {
-                for _ in 0..*count {
-                    new_bucket.push(*ch);
-                }
-            }
+pub fn find_key(buckets: &[HashMap<char, usize>]) -> String {
+    find_key_with_tableau(buckets, Tableau::Vigenere)
+}
+
+// every tableau turns a column into one of 26 substitutions of the plaintext column (a shift for
+// Vigenere, a reversed shift for Beaufort), so the same 26 trial decryptions per column find its key letter
+pub fn find_key_with_tableau(buckets: &[HashMap<char, usize>], tableau: Tableau) -> String {
+    find_key_with_shifts(buckets, tableau, ALPHABET_LEN as u8, Language::English)
+}
+
+// only tests the first `shifts` Caesar shifts per column, e.g. 10 for a Gronsfeld key, scoring
+// them against language's letter frequencies
+pub fn find_key_with_shifts(buckets: &[HashMap<char, usize>], tableau: Tableau, shifts: u8, language: Language) -> String {
+    buckets.iter().map(|bucket| best_shift(bucket, tableau, shifts, language)).collect() // buckets is our grouping based on key length
+}
+
+// find_key_with_shifts for the columns known has no letter for, the rest are known's
+pub fn find_key_locked(buckets: &[HashMap<char, usize>], known: &PartialKey, tableau: Tableau, shifts: u8, language: Language) -> String {
+    buckets
+        .iter()
+        .enumerate()
+        .map(|(i, bucket)| match known.shift_at(i) {
+            Some(shift) => (b'A' + shift) as char,
+            None => best_shift(bucket, tableau, shifts, language),
+        })
+        .collect()
+}
+
+// the key letter whose decryption of the column is closest to language by chi-squared
+fn best_shift(bucket: &HashMap<char, usize>, tableau: Tableau, shifts: u8, language: Language) -> char {
+    let mut min: f64 = f64::MAX;
+    let mut idx: i32 = -1;
+    for j in 0..shifts as u32 { // for each bucket, we need to test 26 caesar ciphers and pick the most likely one based on chi-squared test
 
-            let current_shift = char::from_u32(65 + j).unwrap();
-            let mut phrase = String::with_capacity(1);
-            phrase.push(current_shift);
-
-            let mut phrase = KeyPhrase::new(phrase).unwrap();
-            let new_bucket = decode(&new_bucket, &mut phrase);
-
-            //eprintln!("{}", new_bucket);
-            let mut new_bucket_map: HashMap<char, usize> = HashMap::new();
-            for ch in new_bucket.chars() {
-                if let Some(count) = new_bucket_map.get_mut(&ch) {
-                    *count += 1;
-                }
-                else {
-                    new_bucket_map.insert(ch, 1);
-                }
+        let mut new_bucket = String::with_capacity(100);
+        for (ch, count) in bucket {
+            for _ in 0..*count {
+                new_bucket.push(*ch);
             }
+        }
+
+        let current_shift = char::from_u32(65 + j).unwrap();
+        let mut phrase = String::with_capacity(1);
+        phrase.push(current_shift);
 
-            let chi_sq = calculate_chi_squared(&new_bucket_map, new_bucket.len());
-            if chi_sq < min {
-                min = chi_sq;
-                idx = j as i32;
+        let mut phrase = KeyPhrase::new(phrase).unwrap();
+        let new_bucket = decode_with_tableau(&new_bucket, &mut phrase, tableau).unwrap();
+
+        //eprintln!("{}", new_bucket);
+        let mut new_bucket_map: HashMap<char, usize> = HashMap::new();
+        for ch in new_bucket.chars() {
+            if let Some(count) = new_bucket_map.get_mut(&ch) {
+                *count += 1;
+            }
+            else {
+                new_bucket_map.insert(ch, 1);
             }
         }
-        key.push( char::from_u32((65 + idx) as u32).unwrap());
+
+        let chi_sq = calculate_chi_squared_for(&new_bucket_map, new_bucket.len(), language);
+        if chi_sq < min {
+            min = chi_sq;
+            idx = j as i32;
+        }
     }
-    key
+    char::from_u32((65 + idx) as u32).unwrap()
+}
 
+// find_key_with_tableau over alphabet, trying every one of its symbols as the key for each column
+pub fn find_key_in_alphabet(buckets: &[HashMap<char, usize>], tableau: Tableau, alphabet: &Alphabet, language: Language) -> String {
+    let mut key = String::with_capacity(buckets.len());
+    for bucket in buckets {
+        let len = bucket.values().sum();
+        let chi_squared = |shift: u8| {
+            let mut decrypted: HashMap<char, usize> = HashMap::new();
+            for (c, count) in bucket {
+                let plain = tableau.decrypt_in(alphabet.index_of(*c).unwrap(), shift, alphabet.len());
+                *decrypted.entry(alphabet.symbol(plain)).or_insert(0) += count;
+            }
+            calculate_chi_squared_in(&decrypted, len, alphabet, language)
+        };
+        let (shift, _) = (0..alphabet.len())
+            .map(|shift| (shift as u8, chi_squared(shift as u8)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        key.push(alphabet.symbol(shift));
+    }
+    key
 }
 
 
     #[test]
     fn ngram_compute_score_works() {
         //tested against python code with same cipher text
-        let f = File::open("/home/jwaibong/cse360/hw1/decode_given_length/src/english_quadgrams.txt").unwrap();
-        let ngram = Ngram::new(f);
+        let ngram = Ngram::english();
 
         let mut ciphertext = String::from("Frank knew there was a correct time and place to reveal his secret and this wasn't it. The issue was that the secret might be revealed despite his best attempt to keep it from coming out. At this point, it was out of his control and completely dependant on those around him who also knew the secret. They wouldn't purposely reveal it, or at least he believed that, but they could easily inadvertently expose it. It was going to be a long hour as he nervously eyed everyone around the table hoping they would keep their mouths shut.
         He was an expert but not in a discipline that anyone could fully appreciate. He knew how to hold the cone just right so that the soft server ice-cream fell into it at the precise angle to form a perfect cone each and every time. It had taken years to perfect and he could now do it without even putting any thought behind it. Nobody seemed to fully understand the beauty of this accomplishment except for the new worker who watched in amazement.
         It really didn't matter what they did to him. He's already made up his mind. Whatever came his way, he was prepared for the consequences. He knew in his heart that the sacrifice he made was done with love and not hate no matter how others decided to spin it.");
         ciphertext.retain(|c| c.is_alphabetic()); // delete all non alphabetic characters
         ciphertext.make_ascii_uppercase();
-        assert_eq!(-4052.0636388220714, ngram.compute_score(&ciphertext));
+        // the model stores f32 log probabilities, so allow for rounding against python's f64 result
+        assert!((-4052.0636388220714 - ngram.compute_score(&ciphertext)).abs() < 1e-3);
 
 
         let mut ciphertext = String::from("Rvtuy hlfa ggiei iel h qlpsips xvqq egk diydi gn vrzqee owp qfgedx nrp xapg tytr'g hx. Glq mlzib ubw ggeg xti llqocu qvflg fq vxcsxjfh qdwcmfi apg yctx nsxrqbx mv ybcq mg evbq osfpbd mvx. Ns xume thpbq, gu anr shx aj apg zmoxenp nrp ghtdicuiyx hrtqrwhbq mo xunwr edsnur egn aun eywa oglk qff wrbvrx. Flxf klsmha's thvbsllzv pfzrzp vx, av ta zbytx ud frpuiolr qfbx, otx glqc vviib fefhpl mzewcsorfrgkc rbbsll wq. Gu anr kbmzk mv pb y msaf lbyd el os kcszbtwyc qcxk sscscbmi nvaygk hec ueoki usbmgn hecz abtpq oqii avbgs qbtxuw elna.
@@ -100,5 +200,47 @@ pub fn find_key(buckets: &Vec<HashMap<char, usize>>) -> String {
         Vx ditszv bjha's qnxfik dvxr ulrx hvh fs apa. Ec't eyqinhk qtks rn imf lmah. Iltasscs gnli ume atf, vb ubw cqicediw mco rii pnrficyxuqbq. Ii xmij mz lbz vbysx ggeg xti lhqoggmpd lr qmhx dop bprr vmgl xsol okb osg gegi zs fhhqcs lbv sglqvl kszgeiq ss ftur ba.");
         ciphertext.retain(|c| c.is_alphabetic());
         ciphertext.make_ascii_uppercase();
-        assert_eq!(-8122.3612546735985 ,ngram.compute_score(&ciphertext));
-    }
\ No newline at end of file
+        assert!((-8122.3612546735985 - ngram.compute_score(&ciphertext)).abs() < 1e-3);
+    }
+    #[test]
+    fn gronsfeld_short_text() {
+        // key 2718, too short for the full 26 letter search, which settles on CHBI
+        let ciphertext = "Vof btlbawyf qu ivzkle cpkfz vof bjpsl qhl btlf";
+        let buckets = keyphrase::group_ciphertext(&ciphertext.to_ascii_uppercase(), 4);
+        let (key, plaintext) = decode_given_length_gronsfeld(ciphertext, 4, buckets.clone(), &Ngram::english(), Tableau::Vigenere).unwrap();
+        assert_eq!(key, "2718");
+        assert_eq!(plaintext, "The treasure is buried under the third oak tree");
+        assert_eq!(decode_given_length(ciphertext, 4, buckets).unwrap().0, "CHBI");
+    }
+
+    #[test]
+    fn decode_given_length_rejects_zero_length() {
+        let buckets = keyphrase::group_ciphertext("ZINFF EHPDH", 0);
+        assert!(buckets.is_empty());
+        assert_eq!(Err(VigenereError::ZeroKeyLength), decode_given_length("ZINFF EHPDH", 0, buckets));
+    }
+
+    #[test]
+    fn decode_given_length_rejects_short_ciphertext() {
+        let buckets = keyphrase::group_ciphertext("ZIN", 1);
+        assert_eq!(Err(VigenereError::CiphertextTooShort { letters: 3, needed: 4 }), decode_given_length("ZIN", 1, buckets));
+    }
+
+    #[test]
+    fn decode_given_partial_key_locks_known_letters() {
+        let ciphertext = SECRZUTY_CIPHERTEXT;
+        let buckets = keyphrase::group_ciphertext(&ciphertext.to_ascii_uppercase(), 8);
+        assert_eq!(decode_given_length(ciphertext, 8, buckets.clone()).unwrap().0, "SECRZUTY");
+        let known = PartialKey::new("SEC?R?TY").unwrap();
+        let (key, plaintext) = decode_given_partial_key(ciphertext, &known, buckets, &Ngram::english(), Tableau::Vigenere).unwrap();
+        assert_eq!(key, "SECURITY");
+        assert_eq!(plaintext, SECRZUTY_PLAINTEXT);
+    }
+
+    #[test]
+    fn decode_given_partial_key_rejects_other_length() {
+        let ciphertext = SECRZUTY_CIPHERTEXT;
+        let buckets = keyphrase::group_ciphertext(&ciphertext.to_ascii_uppercase(), 9);
+        let known = PartialKey::new("SEC?R?TY").unwrap();
+        assert_eq!(decode_given_partial_key(ciphertext, &known, buckets, &Ngram::english(), Tableau::Vigenere), Err(VigenereError::KeyLengthMismatch { key: 8, columns: 9 }));
+    }
@@ -0,0 +1,125 @@
+use keyphrase::{Alphabet, KeyPhrase, KeyStream, Quagmire, Tableau, VigenereError};
+
+pub fn encode(plaintext: &str, keyphrase: &mut KeyPhrase) -> String {
+    encode_with_tableau(plaintext, keyphrase, Tableau::Vigenere).expect("a repeating key never runs out")
+}
+
+// same as encode but combines each letter with the key using tableau, e.g. Beaufort, and takes
+// any key stream, so it only fails if the key runs out (a running key shorter than the text)
+pub fn encode_with_tableau<K: KeyStream + ?Sized>(plaintext: &str, key: &mut K, tableau: Tableau) -> Result<String, VigenereError> {
+    encode_in_alphabet(plaintext, key, tableau, &Alphabet::letters())
+}
+
+// same as encode_with_tableau over another alphabet, e.g. A-Z0-9, where the key's shifts are positions in it
+pub fn encode_in_alphabet<K: KeyStream + ?Sized>(plaintext: &str, key: &mut K, tableau: Tableau, alphabet: &Alphabet) -> Result<String, VigenereError> {
+    if !tableau.supports(alphabet.len()) {
+        return Err(VigenereError::UnsupportedTableau(tableau));
+    }
+    encode_symbols(plaintext, key, alphabet, |plain, shift| tableau.encrypt_in(plain, shift, alphabet.len()))
+}
+
+// Quagmire encryption, the keyed alphabets and indicator come from quagmire and the periodic key from key
+pub fn encode_quagmire<K: KeyStream + ?Sized>(plaintext: &str, quagmire: &Quagmire, key: &mut K) -> Result<String, VigenereError> {
+    encode_symbols(plaintext, key, &Alphabet::letters(), |plain, shift| quagmire.encrypt(plain, shift))
+}
+
+// encrypts each symbol of alphabet with the next shift, keeping its case, and leaves everything else alone
+fn encode_symbols<K: KeyStream + ?Sized>(plaintext: &str, key: &mut K, alphabet: &Alphabet, encrypt: impl Fn(u8, u8) -> u8) -> Result<String, VigenereError> {
+    let mut ciphertext = String::with_capacity(plaintext.len());
+    for c in plaintext.chars() {
+        let plain = match alphabet.index_of(c) {
+            Some(plain) => plain,
+            None => {
+                ciphertext.push(c);
+                continue;
+            }
+        };
+        let shift = key.next_shift()?;
+        key.push_plaintext(plain);
+        ciphertext.push(alphabet.symbol_like(encrypt(plain, shift), c));
+    }
+    Ok(ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyphrase::{AutoKey, QuagmireKind, RunningKey};
+    #[test]
+    fn encode1() {
+        let plaintext = "Hello world123!";
+        let phrase = String::from("SECURITY");
+        let mut keyphrase: KeyPhrase  = KeyPhrase::new(phrase).unwrap();
+        assert_eq!("Zinff ehpdh123!", encode(plaintext, &mut keyphrase));
+    }
+
+    #[test]
+    fn encode2() {
+        let plaintext = "hell-o wor ld!";
+        let phrase = String::from("SECURITY");
+        let mut keyphrase: KeyPhrase  = KeyPhrase::new(phrase).unwrap();
+        assert_eq!("zinf-f ehp dh!", encode(plaintext, &mut keyphrase));
+    }
+
+    #[test]
+    fn encode_progressive() {
+        // Trithemius shifts each letter one further than the last: H+0, E+1, L+2, ...
+        assert_eq!("Hfnos buytm123!", encode("Hello world123!", &mut KeyPhrase::trithemius()));
+        // the same as encode1 until the key comes round again as TF
+        let mut keyphrase = KeyPhrase::progressive(String::from("SECURITY"), 1).unwrap();
+        assert_eq!("Zinff ehpei123!", encode("Hello world123!", &mut keyphrase));
+    }
+
+    #[test]
+    fn encode_beaufort() {
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        assert_eq!(Ok(String::from("Larjd mfhhb123!")), encode_with_tableau("Hello world123!", &mut keyphrase, Tableau::Beaufort));
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        assert_eq!(Ok(String::from("Pajrx ovttz123!")), encode_with_tableau("Hello world123!", &mut keyphrase, Tableau::VariantBeaufort));
+    }
+
+    #[test]
+    fn encode_autokey_works() {
+        // https://en.wikipedia.org/wiki/Autokey_cipher
+        let mut autokey = AutoKey::new(String::from("QUEENLY")).unwrap();
+        assert_eq!(Ok(String::from("Qnxepv yt wtwp!")), encode_with_tableau("Attack at dawn!", &mut autokey, Tableau::Vigenere));
+    }
+
+    #[test]
+    fn encode_running_key_works() {
+        // key letters from offset 2 are SECURITYSE, the same as encode1 repeating SECURITY
+        let mut running_key = RunningKey::new("Ye security, secure!", 2).unwrap();
+        assert_eq!(Ok(String::from("Zinff ehpdh123!")), encode_with_tableau("Hello world123!", &mut running_key, Tableau::Vigenere));
+
+        let mut running_key = RunningKey::new("short", 0).unwrap();
+        assert_eq!(Err(VigenereError::KeyTooShort { letters: 5, needed: 6 }), encode_with_tableau("Hello world", &mut running_key, Tableau::Vigenere));
+    }
+
+    #[test]
+    fn encode_other_alphabets() {
+        // digits are enciphered too, e + 3 is 4 + 29 = 33, the digit 7
+        let alphanumeric = Alphabet::alphanumeric();
+        let mut keyphrase = KeyPhrase::in_alphabet("K3Y", &alphanumeric).unwrap();
+        assert_eq!(Ok(String::from("R79VH KYK9NUQD!")), encode_in_alphabet("Hello world123!", &mut keyphrase, Tableau::Vigenere, &alphanumeric));
+
+        let mut keyphrase = KeyPhrase::in_alphabet("!", &Alphabet::printable()).unwrap();
+        assert_eq!(Ok(String::from("Ifmmp!xpsme234\"\n")), encode_in_alphabet("Hello world123!\n", &mut keyphrase, Tableau::Vigenere, &Alphabet::printable()));
+        assert_eq!(Err(VigenereError::UnsupportedTableau(Tableau::Porta)), encode_in_alphabet("Hello", &mut keyphrase, Tableau::Porta, &Alphabet::printable()));
+
+        // the case of other scripts is kept too, Е moves on to Ё and final ς is enciphered as σ
+        let mut keyphrase = KeyPhrase::in_alphabet("б", &Alphabet::russian()).unwrap();
+        assert_eq!(Ok(String::from("Рсйгёу, нйс!")), encode_in_alphabet("Привет, мир!", &mut keyphrase, Tableau::Vigenere, &Alphabet::russian()));
+        let mut keyphrase = KeyPhrase::in_alphabet("Β", &Alphabet::greek()).unwrap();
+        assert_eq!(Ok(String::from("Δζκβ τβτ, λπτνζ!")), encode_in_alphabet("Γεια σας, κοσμε!", &mut keyphrase, Tableau::Vigenere, &Alphabet::greek()));
+    }
+
+    #[test]
+    fn encode_quagmire_works() {
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        let quagmire = Quagmire::new(QuagmireKind::III, "KRYPTOS", None, 'A').unwrap();
+        assert_eq!(Ok(String::from("Ginoz oyvjh123!")), encode_quagmire("Hello world123!", &quagmire, &mut keyphrase));
+        let mut keyphrase = KeyPhrase::new(String::from("SECURITY")).unwrap();
+        let quagmire = Quagmire::new(QuagmireKind::IV, "KRYPTOS", Some("FLOWER"), 'C').unwrap();
+        assert_eq!(Ok(String::from("Yanwl fnnlr123!")), encode_quagmire("Hello world123!", &quagmire, &mut keyphrase));
+    }
+}
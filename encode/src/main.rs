@@ -1,67 +0,0 @@
-use keyphrase::KeyPhrase;
-use std::io::{self, BufRead};
-
-const ASCII_UPPER_OFFSET: u8 = 65;
-const ASCII_LOWER_OFFSET: u8 = 97; 
-const KEYPHRASE_LEN: u8 = 26; 
-fn main(){
-    // code snippet to read from stdin taken from
-    // https://stackoverflow.com/questions/30186037/how-can-i-read-a-single-line-from-stdin
-    let mut plaintext = String::new();
-    let stdin = io::stdin();
-    let mut phrase  = String::new();
-    stdin.lock().read_line(&mut plaintext).unwrap();
-    stdin.lock().read_line(&mut phrase).unwrap();
-
-    phrase.pop(); // pop off LF
-    plaintext.pop(); // pop off LF
-    
-    let mut keyphrase = KeyPhrase::new(phrase).unwrap();
-    let ciphertext = encode(&plaintext, &mut keyphrase);
-    println!("{}",ciphertext);
-
-}
-
-pub fn encode(plaintext: &str, keyphrase: &mut KeyPhrase) -> String {
-    let mut ciphertext = String::with_capacity(plaintext.len());
-
-
-    for c in plaintext.chars() {
-        if c.is_ascii_lowercase() {
-            let offset = keyphrase.give_next_offset();
-            let new_char = (((c as u8 - ASCII_LOWER_OFFSET + offset) % KEYPHRASE_LEN) + ASCII_LOWER_OFFSET) as char;
-            //println!("{} + {}: {}", c, offset, new_char);
-            ciphertext.push(new_char);
-        }
-        else if c.is_ascii_uppercase() {
-            let offset = keyphrase.give_next_offset();
-            let new_char = (((c as u8 - ASCII_UPPER_OFFSET + offset) % KEYPHRASE_LEN) + ASCII_UPPER_OFFSET) as char;
-            //println!("{} + {}: {}", c, offset, new_char);
-            ciphertext.push(new_char);
-        }
-        else {
-            ciphertext.push(c);
-        }
-    }
-    ciphertext
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn encode1() {
-        let plaintext = "Hello world123!";
-        let phrase = String::from("SECURITY");
-        let mut keyphrase: KeyPhrase  = KeyPhrase::new(phrase).unwrap();
-        assert_eq!("Zinff ehpdh123!", encode(plaintext, &mut keyphrase));
-    }
-
-    #[test]
-    fn encode2() {
-        let plaintext = "hell-o wor ld!";
-        let phrase = String::from("SECURITY");
-        let mut keyphrase: KeyPhrase  = KeyPhrase::new(phrase).unwrap();
-        assert_eq!("zinf-f ehp dh!", encode(plaintext, &mut keyphrase));
-    }
-}
\ No newline at end of file
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::VigenereError;
+
+const MAX_SYMBOLS: usize = 256; // shifts are u8
+const ASCII_LEN: usize = 128;
+
+/*
+    The ordered symbols a cipher works over, the shift of a key symbol is its position. The usual
+    A-Z leaves digits and punctuation alone, but plenty of CTF and older messages encipher those
+    too, over A-Z0-9 or all 95 printable ASCII characters, and older ones may be in another
+    script altogether, such as the 33 letters of Russian or the 24 of Greek. Symbols outside the
+    alphabet are passed through unchanged and don't use up a key position.
+
+    An alphabet without both cases of any letter ignores case, so a and A (or я and Я) are the
+    same symbol. If it is all letters the case of the text is kept, as A-Z always has, otherwise
+    a letter can come out as a digit and back as either case, so everything is written as in the
+    alphabet. One with both cases (printable ASCII) treats them as different symbols. Case is
+    Unicode's, where a letter changes to a single other letter, so Greek final ς is read as Σ but
+    written back as σ.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+    fold_case: bool,
+    keep_case: bool,
+    ascii: [Option<u8>; ASCII_LEN], // position of every ASCII character, so looking one up is an index
+    others: HashMap<char, u8>,      // and of everything else
+}
+
+impl Alphabet {
+    pub fn letters() -> Alphabet {
+        Alphabet::custom("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+
+    pub fn alphanumeric() -> Alphabet {
+        Alphabet::custom("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap()
+    }
+
+    // space to ~
+    pub fn printable() -> Alphabet {
+        Alphabet::custom(&(' '..='~').collect::<String>()).unwrap()
+    }
+
+    pub fn russian() -> Alphabet {
+        Alphabet::custom("АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ").unwrap()
+    }
+
+    pub fn greek() -> Alphabet {
+        Alphabet::custom("ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ").unwrap()
+    }
+
+    // the symbols in shift order, each at most once
+    pub fn custom(symbols: &str) -> Result<Alphabet, VigenereError> {
+        let symbols: Vec<char> = symbols.chars().collect();
+        if symbols.len() < 2 {
+            return Err(VigenereError::InvalidAlphabet(String::from("it needs at least two symbols")));
+        }
+        if symbols.len() > MAX_SYMBOLS {
+            return Err(VigenereError::InvalidAlphabet(format!("it has {} symbols but at most {} are allowed", symbols.len(), MAX_SYMBOLS)));
+        }
+        if let Some((i, c)) = symbols.iter().enumerate().find(|(i, c)| symbols[..*i].contains(c)) {
+            return Err(VigenereError::InvalidAlphabet(format!("{:?} appears more than once, the second time at position {}", c, i)));
+        }
+
+        let fold_case = !symbols.iter().any(|&c| c.is_lowercase() && symbols.contains(&to_upper(c)));
+        let mut ascii = [None; ASCII_LEN];
+        let mut others = HashMap::new();
+        for (i, &c) in symbols.iter().enumerate() {
+            let cases = if fold_case { vec![c, to_lower(c), to_upper(c)] } else { vec![c] };
+            for c in cases {
+                if c.is_ascii() {
+                    ascii[c as usize] = Some(i as u8);
+                }
+                else {
+                    others.insert(c, i as u8);
+                }
+            }
+        }
+        let keep_case = fold_case && symbols.iter().all(|c| c.is_alphabetic());
+        Ok(Alphabet { symbols, fold_case, keep_case, ascii, others })
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    // never true, custom refuses alphabets of fewer than two symbols
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    // the shift c stands for, None for anything the cipher passes through
+    pub fn index_of(&self, c: char) -> Option<u8> {
+        if c.is_ascii() {
+            self.ascii[c as usize]
+        }
+        else if let Some(&index) = self.others.get(&c) {
+            Some(index)
+        }
+        else if self.fold_case && to_upper(c) != c {
+            self.index_of(to_upper(c)) // a letter like ς whose upper case has another lower case
+        }
+        else {
+            None
+        }
+    }
+
+    // the symbol as it is written in the alphabet
+    pub fn symbol(&self, index: u8) -> char {
+        self.symbols[index as usize]
+    }
+
+    // the symbol in the same case as like, for alphabets that keep the case of the text
+    pub fn symbol_like(&self, index: u8, like: char) -> char {
+        let symbol = self.symbol(index);
+        if !self.keep_case {
+            symbol
+        }
+        else if like.is_lowercase() {
+            to_lower(symbol)
+        }
+        else {
+            to_upper(symbol)
+        }
+    }
+
+    // how many characters of text are enciphered, i.e. use up a key position
+    pub fn count(&self, text: &str) -> usize {
+        text.chars().filter(|&c| self.index_of(c).is_some()).count()
+    }
+
+    // IOC of uniformly random symbols, what text under a wrong key length looks like
+    pub fn random_ioc(&self) -> f64 {
+        1.0 / self.len() as f64
+    }
+}
+
+// the letter in the other case, or c itself when it has none or it takes more than one letter (ß to SS)
+fn to_upper(c: char) -> char {
+    single(c.to_uppercase()).unwrap_or(c)
+}
+
+fn to_lower(c: char) -> char {
+    single(c.to_lowercase()).unwrap_or(c)
+}
+
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::letters()
+    }
+}
+
+// letters, alphanumeric, printable, russian or greek, anything else is taken as the symbols themselves
+impl FromStr for Alphabet {
+    type Err = VigenereError;
+
+    fn from_str(s: &str) -> Result<Alphabet, VigenereError> {
+        match s.to_ascii_lowercase().as_str() {
+            "letters" => Ok(Alphabet::letters()),
+            "alphanumeric" => Ok(Alphabet::alphanumeric()),
+            "printable" => Ok(Alphabet::printable()),
+            "russian" => Ok(Alphabet::russian()),
+            "greek" => Ok(Alphabet::greek()),
+            _ => Alphabet::custom(s),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_symbols() {
+        let letters = Alphabet::letters();
+        assert_eq!((letters.index_of('C'), letters.index_of('c'), letters.index_of('3')), (Some(2), Some(2), None));
+        assert_eq!((letters.symbol_like(2, 'x'), letters.symbol_like(2, 'X')), ('c', 'C'));
+
+        let alphanumeric: Alphabet = "alphanumeric".parse().unwrap();
+        assert_eq!((alphanumeric.len(), alphanumeric.index_of('0'), alphanumeric.index_of('z')), (36, Some(26), Some(25)));
+        assert_eq!(alphanumeric.symbol_like(25, 'x'), 'Z');
+
+        let printable = Alphabet::printable();
+        assert_eq!((printable.len(), printable.index_of(' '), printable.index_of('A'), printable.index_of('a')), (95, Some(0), Some(33), Some(65)));
+        assert_eq!(printable.symbol_like(65, 'Q'), 'a');
+        assert_eq!(printable.count("Hi there!\n"), 9);
+
+        let greek = Alphabet::custom("αβγδ").unwrap();
+        assert_eq!((greek.index_of('γ'), greek.index_of('Γ'), greek.symbol(3)), (Some(2), Some(2), 'δ'));
+
+        let russian = Alphabet::russian();
+        assert_eq!((russian.len(), russian.index_of('ё'), russian.index_of('Я'), russian.index_of('я')), (33, Some(6), Some(32), Some(32)));
+        assert_eq!((russian.symbol_like(32, 'ж'), russian.symbol_like(32, 'Ж')), ('я', 'Я'));
+        assert_eq!(russian.count("Привет, мир! Hi"), 9);
+
+        let greek = Alphabet::greek();
+        assert_eq!((greek.len(), greek.index_of('ς'), greek.index_of('σ')), (24, Some(17), Some(17)));
+    }
+
+    #[test]
+    fn rejects_bad_alphabets() {
+        assert!(matches!(Alphabet::custom("A"), Err(VigenereError::InvalidAlphabet(_))));
+        assert!(matches!("ABCA".parse::<Alphabet>(), Err(VigenereError::InvalidAlphabet(_))));
+        assert!(Alphabet::custom(&('\u{100}'..'\u{300}').collect::<String>()).is_err());
+    }
+}
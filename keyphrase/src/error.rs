@@ -0,0 +1,44 @@
+use std::fmt;
+
+use crate::{Language, Tableau};
+
+// every way the encoders and crackers can fail on bad input, shared by all crates in the workspace
+#[derive(Debug, Clone, PartialEq)]
+pub enum VigenereError {
+    InvalidKeyCharacter(char),
+    EmptyKey,
+    KeyTooShort { letters: usize, needed: usize }, // a running key text that ends before the message does
+    ZeroKeyLength,
+    KeyLengthMismatch { key: usize, columns: usize }, // a key given with ciphertext grouped for another length
+    MissingModel(String),
+    CorruptModel { line: usize, reason: String },
+    CiphertextTooShort { letters: usize, needed: usize },
+    UnsupportedTableau(Tableau), // a cracker that relies on key letters adding up, which Porta's don't
+    InvalidAlphabet(String), // why the symbols can't be an Alphabet
+    EmptyCrib, // known plaintext with no letters in it
+    UnknownLanguage(String), // neither the name nor the two letter code of a Language
+}
+
+impl fmt::Display for VigenereError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VigenereError::InvalidKeyCharacter(c) => write!(f, "invalid key character {:?}: keys may only contain letters A-Z (or the symbols of the alphabet in use), or digits 0-9 for Gronsfeld", c),
+            VigenereError::EmptyKey => write!(f, "the key is empty"),
+            VigenereError::KeyTooShort { letters, needed } => write!(f, "the key text has {} letters from the offset on but the message needs {}", letters, needed),
+            VigenereError::ZeroKeyLength => write!(f, "the key length must be at least 1"),
+            VigenereError::KeyLengthMismatch { key, columns } => write!(f, "the key has {} letters but the ciphertext is grouped into {} columns", key, columns),
+            VigenereError::MissingModel(reason) => write!(f, "could not load n-gram model: {}", reason),
+            VigenereError::CorruptModel { line, reason } => write!(f, "n-gram model is corrupt at line {}: {}", line, reason),
+            VigenereError::CiphertextTooShort { letters, needed } => write!(f, "ciphertext has {} letters but at least {} are needed to analyse it", letters, needed),
+            VigenereError::UnsupportedTableau(tableau) => write!(f, "the {} cipher is not supported here", tableau),
+            VigenereError::InvalidAlphabet(reason) => write!(f, "invalid alphabet: {}", reason),
+            VigenereError::EmptyCrib => write!(f, "the crib has no letters"),
+            VigenereError::UnknownLanguage(name) => {
+                let names: Vec<&str> = Language::ALL.iter().map(|language| language.name()).collect();
+                write!(f, "unknown language {}, expected one of {}", name, names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for VigenereError {}
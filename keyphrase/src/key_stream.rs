@@ -0,0 +1,112 @@
+/*
+    Anything that hands out one shift per letter, so encoding and decoding don't care where the
+    shifts come from: a repeating keyword (progressive and Gronsfeld keys included), an autokey
+    or a running key. Shifts are 0..=25 (A = 0), or positions in another Alphabet, and only
+    the symbols of that alphabet use them up.
+*/
+use crate::{AutoKey, KeyPhrase, RunningKey, VigenereError};
+
+pub trait KeyStream {
+    // the shift for the next letter, only a key that can run out (a running key) ever fails
+    fn next_shift(&mut self) -> Result<u8, VigenereError>;
+
+    // the plaintext (0..=25) of the letter the last shift was used for, called straight after
+    // next_shift. only an autokey needs it, for everything else it does nothing
+    fn push_plaintext(&mut self, _plain: u8) {}
+
+    // back to the first shift, as freshly made
+    fn reset(&mut self);
+
+    // how many letters before the shifts repeat, None if they never do
+    fn period(&self) -> Option<usize>;
+}
+
+impl KeyStream for KeyPhrase {
+    fn next_shift(&mut self) -> Result<u8, VigenereError> {
+        Ok(self.give_next_offset())
+    }
+
+    fn reset(&mut self) {
+        self.current_idx = 0;
+        self.advance = 0;
+    }
+
+    // a progressive key only comes back round once the advance does, after 26 / gcd(step, 26) passes
+    // (or the size of whatever alphabet it is in)
+    fn period(&self) -> Option<usize> {
+        let passes = if self.step == 0 { 1 } else { self.size / gcd(self.step as usize, self.size) };
+        Some(self.shifts.len() * passes)
+    }
+}
+
+impl KeyStream for AutoKey {
+    fn next_shift(&mut self) -> Result<u8, VigenereError> {
+        Ok(self.next_offset())
+    }
+
+    fn push_plaintext(&mut self, plain: u8) {
+        AutoKey::push_plaintext(self, plain);
+    }
+
+    fn reset(&mut self) {
+        self.pending = self.primer.iter().copied().collect();
+    }
+
+    fn period(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl KeyStream for RunningKey {
+    fn next_shift(&mut self) -> Result<u8, VigenereError> {
+        self.give_next_offset()
+    }
+
+    fn reset(&mut self) {
+        self.current_idx = 0;
+    }
+
+    fn period(&self) -> Option<usize> {
+        None
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periods() {
+        assert_eq!(Some(8), KeyPhrase::new(String::from("SECURITY")).unwrap().period());
+        assert_eq!(Some(8 * 26), KeyPhrase::progressive(String::from("SECURITY"), 1).unwrap().period());
+        assert_eq!(Some(8 * 13), KeyPhrase::progressive(String::from("SECURITY"), 4).unwrap().period());
+        assert_eq!(Some(8 * 2), KeyPhrase::progressive(String::from("SECURITY"), 13).unwrap().period());
+        assert_eq!(None, AutoKey::new(String::from("QUEENLY")).unwrap().period());
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut trithemius = KeyPhrase::trithemius();
+        let first: Vec<u8> = (0..30).map(|_| trithemius.next_shift().unwrap()).collect();
+        trithemius.reset();
+        assert_eq!(first, (0..30).map(|_| trithemius.next_shift().unwrap()).collect::<Vec<u8>>());
+
+        let mut autokey = AutoKey::new(String::from("AB")).unwrap();
+        for plain in [7, 8, 9] {
+            autokey.next_shift().unwrap();
+            KeyStream::push_plaintext(&mut autokey, plain);
+        }
+        autokey.reset();
+        assert_eq!((Ok(0), Ok(1)), (autokey.next_shift(), autokey.next_shift()));
+
+        let mut running = RunningKey::new("ab", 0).unwrap();
+        assert!(running.next_shift().is_ok() && running.next_shift().is_ok() && running.next_shift().is_err());
+        running.reset();
+        assert_eq!(Ok(0), running.next_shift());
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{VigenereError, CHI_SQUARED_ENGLISH_EXPECTED_FREQ};
+
+/*
+    What the crackers expect the plaintext to look like: its letter frequencies, for chi-squared,
+    and its index of coincidence, for finding the key length. The IOCs are Friedman's, divided by
+    26 to turn them into the raw form calculate_ioc gives, except English which keeps the figure
+    this project has always been tuned with.
+
+    The frequencies leave out accented letters and ß, so they are scaled up to sum to 1 as if
+    those had been dropped from the text too. Russian is written in its own 33 letters, so it can
+    only be cracked over Alphabet::russian, the n-gram model and the crackers built on it know
+    A-Z alone, see decode_given_key::Ngram::for_language for the models built in.
+
+    https://en.wikipedia.org/wiki/Letter_frequency
+    https://ru.wikipedia.org/wiki/Частотность
+    https://en.wikipedia.org/wiki/Index_of_coincidence
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Russian,
+}
+
+const LATIN_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const RUSSIAN_LETTERS: &str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+
+// percentages, A first
+const GERMAN_FREQ: [f64; 26] = [6.516, 1.886, 2.732, 5.076, 16.396, 1.656, 3.009, 4.577, 6.550, 0.268, 1.417, 3.437, 2.534,
+9.776, 2.594, 0.670, 0.018, 7.003, 7.270, 6.154, 4.166, 0.846, 1.921, 0.034, 0.039, 1.134];
+const FRENCH_FREQ: [f64; 26] = [7.636, 0.901, 3.260, 3.669, 14.715, 1.066, 0.866, 0.737, 7.529, 0.613, 0.049, 5.456, 2.968,
+7.095, 5.796, 2.521, 1.362, 6.693, 7.948, 7.244, 6.311, 1.838, 0.114, 0.427, 0.128, 0.326];
+const SPANISH_FREQ: [f64; 26] = [11.525, 2.215, 4.019, 5.010, 12.181, 0.692, 1.768, 0.703, 6.247, 0.493, 0.011, 4.967, 3.157,
+6.712, 8.683, 2.510, 0.877, 6.871, 7.977, 4.632, 2.927, 1.138, 0.017, 0.215, 1.008, 0.467];
+const ITALIAN_FREQ: [f64; 26] = [11.745, 0.927, 4.501, 3.736, 11.792, 1.153, 1.644, 0.636, 10.143, 0.011, 0.009, 6.510, 2.512,
+6.883, 9.832, 3.056, 0.505, 6.367, 4.981, 5.623, 3.011, 2.097, 0.033, 0.003, 0.020, 1.181];
+const PORTUGUESE_FREQ: [f64; 26] = [14.634, 1.043, 3.882, 4.992, 12.570, 1.023, 1.303, 0.781, 6.186, 0.397, 0.015, 2.779, 4.738,
+4.446, 9.735, 2.523, 1.204, 6.530, 6.805, 4.336, 3.639, 1.575, 0.037, 0.253, 0.006, 0.470];
+// А first, Ё after Е
+const RUSSIAN_FREQ: [f64; 33] = [8.01, 1.59, 4.54, 1.70, 2.98, 8.45, 0.04, 0.94, 1.65, 7.35, 1.21, 3.49, 4.40, 3.21, 6.70, 10.97,
+2.81, 4.73, 5.47, 6.26, 2.62, 0.26, 0.97, 0.48, 1.44, 0.73, 0.36, 0.04, 1.90, 1.74, 0.32, 0.64, 2.01];
+
+impl Language {
+    pub const ALL: [Language; 7] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Italian,
+        Language::Portuguese,
+        Language::Russian,
+    ];
+
+    // the upper case letters the language is written in, in alphabetical order
+    pub fn letters(self) -> &'static str {
+        match self {
+            Language::Russian => RUSSIAN_LETTERS,
+            _ => LATIN_LETTERS,
+        }
+    }
+
+    // whether it is written in A-Z, which everything but the Alphabet based crackers assume
+    pub fn is_latin(self) -> bool {
+        self.letters() == LATIN_LETTERS
+    }
+
+    // how often each of letters() turns up, in the same order, summing to 1
+    pub fn letter_frequencies(self) -> Vec<f64> {
+        let percentages: &[f64] = match self {
+            Language::English => return CHI_SQUARED_ENGLISH_EXPECTED_FREQ.to_vec(),
+            Language::German => &GERMAN_FREQ,
+            Language::French => &FRENCH_FREQ,
+            Language::Spanish => &SPANISH_FREQ,
+            Language::Italian => &ITALIAN_FREQ,
+            Language::Portuguese => &PORTUGUESE_FREQ,
+            Language::Russian => &RUSSIAN_FREQ,
+        };
+        let total: f64 = percentages.iter().sum();
+        percentages.iter().map(|percentage| percentage / total).collect()
+    }
+
+    // the IOC of a long plaintext, what a column under the right key length should come close to
+    pub fn ioc(self) -> f64 {
+        match self {
+            Language::English => 0.068,
+            Language::German => 2.05 / 26.0,
+            Language::French => 2.02 / 26.0,
+            Language::Spanish | Language::Italian | Language::Portuguese => 1.94 / 26.0,
+            Language::Russian => 1.76 / 33.0,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::German => "german",
+            Language::French => "french",
+            Language::Spanish => "spanish",
+            Language::Italian => "italian",
+            Language::Portuguese => "portuguese",
+            Language::Russian => "russian",
+        }
+    }
+
+    // ISO 639-1
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Italian => "it",
+            Language::Portuguese => "pt",
+            Language::Russian => "ru",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// the name or the two letter code
+impl FromStr for Language {
+    type Err = VigenereError;
+
+    fn from_str(s: &str) -> Result<Language, VigenereError> {
+        let s = s.to_ascii_lowercase();
+        match Language::ALL.iter().find(|language| language.name() == s || language.code() == s) {
+            Some(language) => Ok(*language),
+            None => Err(VigenereError::UnknownLanguage(s)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        for language in Language::ALL {
+            assert!((language.letter_frequencies().iter().sum::<f64>() - 1.0).abs() < 1e-3);
+            assert_eq!(language.letter_frequencies().len(), language.letters().chars().count());
+            assert!(language.ioc() > 1.0 / language.letters().chars().count() as f64);
+            assert_eq!(language.name().parse(), Ok(language));
+        }
+        // E is the commonest letter everywhere but Portuguese, where A is, and Russian, where О is
+        let commonest = |language: Language| {
+            let frequencies = language.letter_frequencies();
+            let index = (0..frequencies.len()).max_by(|&a, &b| frequencies[a].total_cmp(&frequencies[b])).unwrap();
+            language.letters().chars().nth(index).unwrap()
+        };
+        assert_eq!(Language::ALL.map(commonest), ['E', 'E', 'E', 'E', 'E', 'A', 'О']);
+        assert!(Language::German.is_latin() && !Language::Russian.is_latin());
+        assert_eq!("PT".parse(), Ok(Language::Portuguese));
+        assert_eq!("Klingon".parse::<Language>(), Err(VigenereError::UnknownLanguage(String::from("klingon"))));
+    }
+}
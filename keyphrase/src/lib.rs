@@ -1,8 +1,30 @@
 
-use std::collections::HashMap;
+mod alphabet;
+mod error;
+mod key_stream;
+mod language;
+mod partial_key;
+mod quagmire;
+mod tableau;
+
+use std::collections::{HashMap, VecDeque};
+
+pub use alphabet::Alphabet;
+pub use error::VigenereError;
+pub use key_stream::KeyStream;
+pub use language::Language;
+pub use partial_key::{PartialKey, UNKNOWN_KEY_LETTER};
+pub use quagmire::{keyed_alphabet, Quagmire, QuagmireKind, STRAIGHT_ALPHABET};
+pub use tableau::Tableau;
 
 
 const ASCII_UPPER_OFFSET: u8 = 65;
+const ASCII_DIGIT_OFFSET: u8 = 48;
+
+// letters A-Z, so also how many different shifts a key letter can give
+pub const ALPHABET_LEN: usize = 26;
+// a Gronsfeld key only has the digits 0-9
+pub const GRONSFELD_SHIFTS: u8 = 10;
 //const ASCII_LOWER_OFFSET: u8 = 97; 
 
 //http://cs.wellesley.edu/~fturbak/codman/letterfreq.html
@@ -10,31 +32,79 @@ const CHI_SQUARED_ENGLISH_EXPECTED_FREQ: [f64; 26]= [0.08167,0.01492,0.02782,0.0
 0.04025,0.02406,0.06749,0.07507,0.01929,0.00095,0.05987,0.06327,0.09056,0.02758,0.00978,
 0.02360,0.00150,0.01974,0.00074];
 
+// roughly one character in six of English prose is a space between words, near enough for the rest too
+const ENGLISH_SPACE_FREQ: f64 = 0.18;
+
 
 
 pub struct KeyPhrase {
-    phrase: String,
+    shifts: Vec<u8>, // the phrase as positions in its alphabet
+    size: usize,     // how many symbols that alphabet has, the shifts wrap round at this
     current_idx: usize,
+    step: u8,    // added to every shift after each full pass through the phrase, 0 for a plain repeating key
+    advance: u8, // how far the shifts have moved on so far
 }
 
 impl KeyPhrase {
-    pub fn new(phrase: String) -> Option<KeyPhrase> {
-        if phrase.len() == 0 {
-            return None;
+    // lowercase letters are accepted as their uppercase shifts
+    pub fn new(phrase: String) -> Result<KeyPhrase, VigenereError> {
+        KeyPhrase::in_alphabet(&phrase, &Alphabet::letters())
+    }
+
+    // a key for a cipher over alphabet, every symbol of the phrase must be in it
+    pub fn in_alphabet(phrase: &str, alphabet: &Alphabet) -> Result<KeyPhrase, VigenereError> {
+        if phrase.is_empty() {
+            return Err(VigenereError::EmptyKey);
         }
-        Some(KeyPhrase {
-            phrase, 
-            current_idx : 0
+        let shifts = phrase
+            .chars()
+            .map(|c| alphabet.index_of(c).ok_or(VigenereError::InvalidKeyCharacter(c)))
+            .collect::<Result<Vec<u8>, VigenereError>>()?;
+        Ok(KeyPhrase {
+            shifts, 
+            size: alphabet.len(),
+            current_idx : 0,
+            step: 0,
+            advance: 0,
         })
     }
-    pub fn give_next_offset(&mut self) -> u8 {
 
-        let mut offset = *self.phrase.as_bytes().get(self.current_idx).unwrap() as u32;
-        //println!("{}", offset);
-        offset -= ASCII_UPPER_OFFSET as u32;
+    /*
+        Progressive key: the phrase repeats as usual, but each time round every shift moves on by
+        step, so SECURITY with step 1 continues TFDVSJUZ, UGEWTKVA, ... The period becomes 26
+        times longer (less when step shares a factor with 26), too long for the IOC to spot.
+    */
+    pub fn progressive(phrase: String, step: u8) -> Result<KeyPhrase, VigenereError> {
+        Ok(KeyPhrase::new(phrase)?.with_step(step))
+    }
+
+    // makes any key progressive, the step wraps round at the size of its alphabet
+    pub fn with_step(mut self, step: u8) -> KeyPhrase {
+        self.step = (step as usize % self.size) as u8;
+        self
+    }
+
+    // Trithemius: shifts 0, 1, 2, ... with no key at all, the progressive key A with step 1
+    // https://en.wikipedia.org/wiki/Tabula_recta
+    pub fn trithemius() -> KeyPhrase {
+        KeyPhrase::progressive(String::from("A"), 1).unwrap()
+    }
 
-        if self.current_idx + 1 >= self.phrase.len() {
+    // Gronsfeld key: each digit is a shift of 0-9, kept as the letter A-J with the same shift
+    // https://en.wikipedia.org/wiki/Gronsfeld_cipher
+    pub fn from_digits(digits: &str) -> Result<KeyPhrase, VigenereError> {
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(VigenereError::InvalidKeyCharacter(c));
+        }
+        KeyPhrase::new(digits.bytes().map(|d| (d - ASCII_DIGIT_OFFSET + ASCII_UPPER_OFFSET) as char).collect())
+    }
+
+    pub fn give_next_offset(&mut self) -> u8 {
+        let offset = (self.shifts[self.current_idx] as usize + self.advance as usize) % self.size;
+
+        if self.current_idx + 1 >= self.shifts.len() {
             self.current_idx = 0;
+            self.advance = ((self.advance as usize + self.step as usize) % self.size) as u8;
         }
         else {
             self.current_idx += 1;
@@ -44,33 +114,102 @@ impl KeyPhrase {
 
 }
 
+/*
+    Autokey key stream: the primer is used once and then the plaintext itself becomes the key,
+    so letter i of the plaintext is the key for letter i + primer length. The caller hands back
+    each plaintext letter once it is known, which for decryption is only after its offset is used.
+
+    https://en.wikipedia.org/wiki/Autokey_cipher
+*/
+pub struct AutoKey {
+    primer: Vec<u8>,
+    pending: VecDeque<u8>, // offsets still to be handed out, primer first
+}
+
+impl AutoKey {
+    pub fn new(primer: String) -> Result<AutoKey, VigenereError> {
+        AutoKey::in_alphabet(&primer, &Alphabet::letters())
+    }
+
+    // the primer's symbols must be in alphabet, as must the plaintext's for it to be part of the key
+    pub fn in_alphabet(primer: &str, alphabet: &Alphabet) -> Result<AutoKey, VigenereError> {
+        let primer = KeyPhrase::in_alphabet(primer, alphabet)?.shifts;
+        Ok(AutoKey { pending: primer.iter().copied().collect(), primer })
+    }
+
+    // offset for the next letter, which must be followed by push_plaintext for that letter
+    pub fn next_offset(&mut self) -> u8 {
+        self.pending.pop_front().expect("push_plaintext was not called after next_offset")
+    }
+
+    // the plaintext (0..=25) of the letter next_offset was last used for, it becomes part of the key
+    pub fn push_plaintext(&mut self, plain: u8) {
+        self.pending.push_back(plain);
+    }
+}
+
+/*
+    Running key: the key stream is a long text, usually a passage from a book both sides own,
+    read from a starting letter and never repeated. Only the letters of the text count, so the
+    offset is a number of letters rather than bytes.
+
+    https://en.wikipedia.org/wiki/Running_key_cipher
+*/
+pub struct RunningKey {
+    letters: Vec<u8>, // the key text from the offset on, as positions in its alphabet
+    current_idx: usize,
+}
+
+impl RunningKey {
+    pub fn new(key_text: &str, offset: usize) -> Result<RunningKey, VigenereError> {
+        RunningKey::in_alphabet(key_text, offset, &Alphabet::letters())
+    }
+
+    // only the symbols of key_text in alphabet are used, and offset counts those
+    pub fn in_alphabet(key_text: &str, offset: usize, alphabet: &Alphabet) -> Result<RunningKey, VigenereError> {
+        let letters: Vec<u8> = key_text.chars().filter_map(|c| alphabet.index_of(c)).skip(offset).collect();
+        if letters.is_empty() {
+            return Err(VigenereError::EmptyKey);
+        }
+        Ok(RunningKey { letters, current_idx: 0 })
+    }
+
+    // letters left in the key text, a message can't have more letters than this
+    pub fn remaining(&self) -> usize {
+        self.letters.len() - self.current_idx
+    }
+
+    // fails once the key text runs out instead of wrapping round like KeyPhrase
+    pub fn give_next_offset(&mut self) -> Result<u8, VigenereError> {
+        match self.letters.get(self.current_idx) {
+            Some(&offset) => {
+                self.current_idx += 1;
+                Ok(offset)
+            }
+            None => Err(VigenereError::KeyTooShort { letters: self.letters.len(), needed: self.current_idx + 1 }),
+        }
+    }
+}
+
 // group a ciphertext into key_length buckets
 pub fn group_ciphertext(ciphertext: &str, key_length: usize) -> Vec<HashMap<char, usize>> {
+    group_ciphertext_in(ciphertext, key_length, &Alphabet::letters())
+}
+
+// same as group_ciphertext for a cipher over alphabet, symbols are counted as the alphabet writes them
+pub fn group_ciphertext_in(ciphertext: &str, key_length: usize, alphabet: &Alphabet) -> Vec<HashMap<char, usize>> {
     let mut buckets: Vec<HashMap<char, usize>> = Vec::with_capacity(key_length);
+    if key_length == 0 {
+        return buckets; // nothing to group into
+    }
     for _ in 0..key_length {
 
         buckets.push(HashMap::new());
     }
 
-    let mut i = 0;
-    for c in ciphertext.chars() {
-        if !c.is_ascii_alphabetic() {
-            continue;
-        }
-        if i == key_length {
-            i = 0;
-        }
-
-        
-        let bucket = buckets.get_mut(i).unwrap();
-        if let Some(value) = bucket.get_mut(&c) {
-            *value += 1;
-        }
-        else {
-            bucket.insert(c, 1);
-        }
-
-        i+=1;
+    let symbols = ciphertext.chars().filter_map(|c| alphabet.index_of(c));
+    for (i, index) in symbols.enumerate() {
+        *buckets[i % key_length].entry(alphabet.symbol(index)).or_insert(0) += 1;
     }
     buckets
 }
@@ -100,21 +239,75 @@ pub fn calculate_ioc(bucket: &HashMap<char, usize>) -> f64 {
     ret
 }
 
+/*
+    Friedman test: the IOC of the whole ciphertext falls from the language's IOC towards the IOC
+    of random letters as the key gets longer, so the key length can be estimated from it directly.
+    This is the form that corrects for the length of the text, n letters:
+
+        L = (Kp - Kr) n / ((n - 1) Ko - n Kr + Kp)
+
+    where Ko is the ciphertext IOC, Kp the language IOC and Kr the random IOC. The estimate is
+    rough, off by a few either way on short texts, and is clamped to 1..=n since an IOC at or
+    below random only says the key is about as long as the text.
+
+    https://en.wikipedia.org/wiki/Vigen%C3%A8re_cipher#Friedman_test
+*/
+pub fn friedman_key_length(bucket: &HashMap<char, usize>, language_ioc: f64, random_ioc: f64) -> f64 {
+    let n = bucket.values().sum::<usize>() as f64;
+    if n < 2.0 {
+        return n; // IOC is undefined
+    }
+    let denominator = (n - 1.0) * calculate_ioc(bucket) - n * random_ioc + language_ioc;
+    if denominator <= 0.0 {
+        return n;
+    }
+    ((language_ioc - random_ioc) * n / denominator).clamp(1.0, n)
+}
+
 // formula from 
 // http://practicalcryptography.com/cryptanalysis/text-characterisation/chi-squared-statistic/
 pub fn calculate_chi_squared(bucket: &HashMap<char, usize>, len: usize) -> f64 {
+    calculate_chi_squared_for(bucket, len, Language::English)
+}
+
+// same as calculate_chi_squared against another language's letter frequencies
+pub fn calculate_chi_squared_for(bucket: &HashMap<char, usize>, len: usize, language: Language) -> f64 {
     let mut sum: f64 = 0.0;
 
-    let mut c: u8 = 65;
-    for i in 0..26 {
-        if let Some(actual_count) = bucket.get(&(c as char)) {
-            let expected_count: f64 = len as f64 * CHI_SQUARED_ENGLISH_EXPECTED_FREQ[i]; // denominator
+    for (c, expected_freq) in language.letters().chars().zip(language.letter_frequencies().iter()) {
+        if let Some(actual_count) = bucket.get(&c) {
+            let expected_count: f64 = len as f64 * expected_freq; // denominator
             let numerator: f64 = (*actual_count as f64 - expected_count) * (*actual_count as f64 - expected_count);
-            //eprintln!("{}: {}, expected = {}", c as char, *actual_count, expected_count);
+            //eprintln!("{}: {}, expected = {}", c, *actual_count, expected_count);
             let frac = numerator / expected_count;
             sum += frac;
         }
-        c += 1;
+    }
+    sum
+}
+
+
+/*
+    Chi-squared against language for a bucket grouped with group_ciphertext_in. Unlike
+    calculate_chi_squared every letter counts, missing ones included, since under a wrong key a
+    column of a larger alphabet can come out as nothing but digits and punctuation. The
+    language's letters are looked for in lower case when the alphabet has both cases, as most
+    text is, and the space takes its share of the text when the alphabet has one.
+*/
+pub fn calculate_chi_squared_in(bucket: &HashMap<char, usize>, len: usize, alphabet: &Alphabet, language: Language) -> f64 {
+    let space = if alphabet.index_of(' ').is_some() { ENGLISH_SPACE_FREQ } else { 0.0 };
+    let expected = language
+        .letters()
+        .chars()
+        .zip(language.letter_frequencies().into_iter().map(|freq| freq * (1.0 - space)))
+        .filter_map(|(c, freq)| alphabet.index_of(c.to_lowercase().next().unwrap()).map(|index| (alphabet.symbol(index), freq)))
+        .chain(alphabet.index_of(' ').map(|index| (alphabet.symbol(index), space)));
+
+    let mut sum: f64 = 0.0;
+    for (c, expected_freq) in expected {
+        let expected_count = len as f64 * expected_freq;
+        let actual_count = *bucket.get(&c).unwrap_or(&0) as f64;
+        sum += (actual_count - expected_count) * (actual_count - expected_count) / expected_count;
     }
     sum
 }
@@ -141,4 +334,63 @@ mod tests {
         assert_eq!(30.42091198287971, calculate_chi_squared(&bucket, seq.len()));
     }
 
+    #[test]
+    fn friedman_works() {
+        let english = 0.0667;
+        let random = 1.0 / 26.0;
+        // an IOC equal to the language's means a single alphabet
+        let mut bucket: HashMap<char, usize> = HashMap::new();
+        for (c, count) in ('A'..='Z').zip(CHI_SQUARED_ENGLISH_EXPECTED_FREQ.iter().map(|f| (f * 10000.0).round() as usize)) {
+            bucket.insert(c, count);
+        }
+        assert!((friedman_key_length(&bucket, calculate_ioc(&bucket), random) - 1.0).abs() < 1e-9);
+
+        // flat counts look like a key as long as the text
+        let flat: HashMap<char, usize> = ('A'..='Z').map(|c| (c, 4)).collect();
+        assert_eq!(friedman_key_length(&flat, english, random), 104.0);
+
+        assert_eq!(friedman_key_length(&HashMap::new(), english, random), 0.0);
+    }
+
+    #[test]
+    fn keyphrase_rejects_bad_keys() {
+        assert_eq!(KeyPhrase::new(String::new()).err(), Some(VigenereError::EmptyKey));
+        assert_eq!(KeyPhrase::new(String::from("SEC URITY")).err(), Some(VigenereError::InvalidKeyCharacter(' ')));
+        assert_eq!(KeyPhrase::new(String::from("K3Y")).err(), Some(VigenereError::InvalidKeyCharacter('3')));
+
+        assert!(AutoKey::new(String::from("Q3")).is_err());
+
+        let mut running = RunningKey::new("It was a dark, stormy night", 10).unwrap();
+        assert_eq!(running.remaining(), 11);
+        assert_eq!(Ok(18), running.give_next_offset()); // the s of stormy
+        assert!(RunningKey::new("too short", 8).is_err());
+        let mut running = RunningKey::new("ab", 1).unwrap();
+        assert_eq!(Ok(1), running.give_next_offset());
+        assert_eq!(Err(VigenereError::KeyTooShort { letters: 1, needed: 2 }), running.give_next_offset());
+
+        assert_eq!(KeyPhrase::from_digits("").err(), Some(VigenereError::EmptyKey));
+        assert_eq!(KeyPhrase::from_digits("31A").err(), Some(VigenereError::InvalidKeyCharacter('A')));
+        let mut keyphrase = KeyPhrase::from_digits("90").unwrap();
+        assert_eq!(9, keyphrase.give_next_offset());
+        assert_eq!(0, keyphrase.give_next_offset());
+
+        let mut keyphrase = KeyPhrase::new(String::from("bA")).unwrap();
+        assert_eq!(1, keyphrase.give_next_offset());
+        assert_eq!(0, keyphrase.give_next_offset());
+    }
+
+    #[test]
+    fn progressive_keys() {
+        // YZ moves on by 3 each time round, wrapping past Z
+        let mut keyphrase = KeyPhrase::progressive(String::from("YZ"), 3).unwrap();
+        let offsets: Vec<u8> = (0..6).map(|_| keyphrase.give_next_offset()).collect();
+        assert_eq!(offsets, vec![24, 25, 1, 2, 4, 5]);
+
+        let mut trithemius = KeyPhrase::trithemius();
+        let offsets: Vec<u8> = (0..28).map(|_| trithemius.give_next_offset()).collect();
+        assert_eq!(offsets[..3], [0, 1, 2]);
+        assert_eq!(offsets[25..], [25, 0, 1]);
+        assert!(KeyPhrase::progressive(String::new(), 1).is_err());
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use crate::{Alphabet, VigenereError};
+
+// how an unknown key letter is written
+pub const UNKNOWN_KEY_LETTER: char = '?';
+
+/*
+    A repeating key with only some of its letters known, the rest None. That is what a crib
+    gives when it is shorter than the key, or what is left when an analyst has worked out a few
+    columns by hand. Letters are shifts 0..=25 as in KeyPhrase.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialKey {
+    shifts: Vec<Option<u8>>,
+}
+
+impl PartialKey {
+    // a key written with ? for the letters not known, e.g. SEC?R?TY, lowercase letters as uppercase
+    pub fn new(phrase: &str) -> Result<PartialKey, VigenereError> {
+        if phrase.is_empty() {
+            return Err(VigenereError::EmptyKey);
+        }
+        let alphabet = Alphabet::letters();
+        let shifts = phrase
+            .chars()
+            .map(|c| match c {
+                UNKNOWN_KEY_LETTER => Ok(None),
+                _ => alphabet.index_of(c).map(Some).ok_or(VigenereError::InvalidKeyCharacter(c)),
+            })
+            .collect::<Result<Vec<Option<u8>>, VigenereError>>()?;
+        PartialKey::from_shifts(shifts)
+    }
+
+    // a key of length letters, none of them known yet
+    pub fn unknown(length: usize) -> Result<PartialKey, VigenereError> {
+        PartialKey::from_shifts(vec![None; length])
+    }
+
+    pub fn from_shifts(shifts: Vec<Option<u8>>) -> Result<PartialKey, VigenereError> {
+        if shifts.is_empty() {
+            return Err(VigenereError::ZeroKeyLength);
+        }
+        Ok(PartialKey { shifts })
+    }
+
+    pub fn len(&self) -> usize {
+        self.shifts.len()
+    }
+
+    // never true, from_shifts refuses an empty key
+    pub fn is_empty(&self) -> bool {
+        self.shifts.is_empty()
+    }
+
+    pub fn shifts(&self) -> &[Option<u8>] {
+        &self.shifts
+    }
+
+    // the shift for the letter at index of the text, the key repeating as usual
+    pub fn shift_at(&self, index: usize) -> Option<u8> {
+        self.shifts[index % self.shifts.len()]
+    }
+
+    pub fn set(&mut self, position: usize, shift: Option<u8>) {
+        self.shifts[position] = shift;
+    }
+
+    pub fn known(&self) -> usize {
+        self.shifts.iter().filter(|shift| shift.is_some()).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.known() == self.len()
+    }
+}
+
+// the known letters A-Z with ? for the rest, e.g. SEC?R?TY
+impl fmt::Display for PartialKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key: String = self
+            .shifts
+            .iter()
+            .map(|shift| match shift {
+                Some(shift) => (shift + b'A') as char,
+                None => UNKNOWN_KEY_LETTER,
+            })
+            .collect();
+        write!(f, "{}", key)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_keys() {
+        let mut key = PartialKey::unknown(4).unwrap();
+        key.set(1, Some(4));
+        key.set(3, Some(24));
+        assert_eq!(key.to_string(), "?E?Y");
+        assert_eq!((key.known(), key.is_complete(), key.shift_at(5), key.shift_at(6)), (2, false, Some(4), None));
+        assert_eq!(PartialKey::unknown(0), Err(VigenereError::ZeroKeyLength));
+
+        let key = PartialKey::new("sec?R?TY").unwrap();
+        assert_eq!((key.to_string(), key.known()), (String::from("SEC?R?TY"), 6));
+        assert_eq!(PartialKey::new(""), Err(VigenereError::EmptyKey));
+        assert_eq!(PartialKey::new("SEC*"), Err(VigenereError::InvalidKeyCharacter('*')));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{VigenereError, ALPHABET_LEN};
+
+pub const STRAIGHT_ALPHABET: [u8; ALPHABET_LEN] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+];
+
+/*
+    Quagmire ciphers are Vigenere with the plaintext alphabet, the ciphertext alphabet or both
+    mixed up by a keyword. The ciphertext alphabet slides along under the plaintext alphabet, and
+    each key letter picks the position where it sits under the indicator letter.
+
+    I    keyed plaintext alphabet, straight ciphertext alphabet
+    II   straight plaintext alphabet, keyed ciphertext alphabet
+    III  the same keyed alphabet for both
+    IV   different keyed alphabets for each
+
+    https://www.cryptogram.org/downloads/aca.info/ciphers/Quagmire.pdf
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuagmireKind {
+    I,
+    II,
+    III,
+    IV,
+}
+
+impl QuagmireKind {
+    pub const ALL: [QuagmireKind; 4] = [QuagmireKind::I, QuagmireKind::II, QuagmireKind::III, QuagmireKind::IV];
+}
+
+impl fmt::Display for QuagmireKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numeral = match self {
+            QuagmireKind::I => "I",
+            QuagmireKind::II => "II",
+            QuagmireKind::III => "III",
+            QuagmireKind::IV => "IV",
+        };
+        write!(f, "{}", numeral)
+    }
+}
+
+impl FromStr for QuagmireKind {
+    type Err = String;
+
+    // accepts 1-4 as well as I-IV
+    fn from_str(s: &str) -> Result<QuagmireKind, String> {
+        match s.to_ascii_uppercase().as_str() {
+            "1" | "I" => Ok(QuagmireKind::I),
+            "2" | "II" => Ok(QuagmireKind::II),
+            "3" | "III" => Ok(QuagmireKind::III),
+            "4" | "IV" => Ok(QuagmireKind::IV),
+            _ => Err(format!("unknown Quagmire type {}, expected 1, 2, 3 or 4", s)),
+        }
+    }
+}
+
+// keyword letters in order with repeats dropped, then the rest of the alphabet, e.g. KRYPTOSABCDEFGHIJLMNQUVWXZ
+pub fn keyed_alphabet(keyword: &str) -> Result<[u8; ALPHABET_LEN], VigenereError> {
+    if keyword.is_empty() {
+        return Err(VigenereError::EmptyKey);
+    }
+    if let Some(c) = keyword.chars().find(|c| !c.is_ascii_alphabetic()) {
+        return Err(VigenereError::InvalidKeyCharacter(c));
+    }
+
+    let mut alphabet = [0; ALPHABET_LEN];
+    let mut used = [false; ALPHABET_LEN];
+    let mut len = 0;
+    let letters = keyword.bytes().map(|b| b.to_ascii_uppercase() - b'A').chain(STRAIGHT_ALPHABET);
+    for letter in letters {
+        if !used[letter as usize] {
+            used[letter as usize] = true;
+            alphabet[len] = letter;
+            len += 1;
+        }
+    }
+    Ok(alphabet)
+}
+
+// the pair of alphabets and the indicator, the periodic key is supplied separately
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quagmire {
+    plain: [u8; ALPHABET_LEN],
+    cipher: [u8; ALPHABET_LEN],
+    plain_position: [u8; ALPHABET_LEN],  // inverse of plain
+    cipher_position: [u8; ALPHABET_LEN], // inverse of cipher
+    indicator_position: u8,              // where the indicator letter is in the plaintext alphabet
+}
+
+impl Quagmire {
+    /*
+        Builds the alphabets for one of the four types. Type IV needs cipher_keyword for its
+        ciphertext alphabet; the other types take their alphabets from keyword alone.
+    */
+    pub fn new(kind: QuagmireKind, keyword: &str, cipher_keyword: Option<&str>, indicator: char) -> Result<Quagmire, VigenereError> {
+        let keyed = keyed_alphabet(keyword)?;
+        let (plain, cipher) = match kind {
+            QuagmireKind::I => (keyed, STRAIGHT_ALPHABET),
+            QuagmireKind::II => (STRAIGHT_ALPHABET, keyed),
+            QuagmireKind::III => (keyed, keyed),
+            QuagmireKind::IV => match cipher_keyword {
+                Some(cipher_keyword) => (keyed, keyed_alphabet(cipher_keyword)?),
+                None => return Err(VigenereError::EmptyKey),
+            },
+        };
+        if !indicator.is_ascii_alphabetic() {
+            return Err(VigenereError::InvalidKeyCharacter(indicator));
+        }
+        Ok(Quagmire::from_alphabets(plain, cipher, indicator.to_ascii_uppercase() as u8 - b'A'))
+    }
+
+    // alphabets are permutations of 0..=25, used directly by solvers
+    pub fn from_alphabets(plain: [u8; ALPHABET_LEN], cipher: [u8; ALPHABET_LEN], indicator: u8) -> Quagmire {
+        let mut plain_position = [0; ALPHABET_LEN];
+        let mut cipher_position = [0; ALPHABET_LEN];
+        for i in 0..ALPHABET_LEN {
+            plain_position[plain[i] as usize] = i as u8;
+            cipher_position[cipher[i] as usize] = i as u8;
+        }
+        Quagmire { plain, cipher, plain_position, cipher_position, indicator_position: plain_position[indicator as usize] }
+    }
+
+    pub fn plain_alphabet(&self) -> String {
+        self.plain.iter().map(|&l| (l + b'A') as char).collect()
+    }
+
+    pub fn cipher_alphabet(&self) -> String {
+        self.cipher.iter().map(|&l| (l + b'A') as char).collect()
+    }
+
+    // how far the ciphertext alphabet is slid for this key letter
+    pub fn shift(&self, key: u8) -> u8 {
+        ((ALPHABET_LEN as u8 + self.cipher_position[key as usize] - self.indicator_position) as usize % ALPHABET_LEN) as u8
+    }
+
+    // the key letter that gives this shift, the inverse of shift
+    pub fn key_letter(&self, shift: u8) -> u8 {
+        self.cipher[(shift + self.indicator_position) as usize % ALPHABET_LEN]
+    }
+
+    pub fn encrypt(&self, plain: u8, key: u8) -> u8 {
+        let position = self.plain_position[plain as usize] + self.shift(key);
+        self.cipher[position as usize % ALPHABET_LEN]
+    }
+
+    pub fn decrypt(&self, cipher: u8, key: u8) -> u8 {
+        let position = ALPHABET_LEN as u8 + self.cipher_position[cipher as usize] - self.shift(key);
+        self.plain[position as usize % ALPHABET_LEN]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(text: &str) -> Vec<u8> {
+        text.bytes().map(|b| b - b'A').collect()
+    }
+
+    #[test]
+    fn builds_keyed_alphabets() {
+        assert_eq!(keyed_alphabet("Kryptos").unwrap().to_vec(), letters("KRYPTOSABCDEFGHIJLMNQUVWXZ"));
+        assert_eq!(keyed_alphabet("BALLOON").unwrap().to_vec(), letters("BALONCDEFGHIJKMPQRSTUVWXYZ"));
+        assert_eq!(keyed_alphabet("").err(), Some(VigenereError::EmptyKey));
+        assert_eq!(keyed_alphabet("NO WAY").err(), Some(VigenereError::InvalidKeyCharacter(' ')));
+    }
+
+    #[test]
+    fn quagmire_round_trip() {
+        for kind in QuagmireKind::ALL {
+            let quagmire = Quagmire::new(kind, "SPRINGFEVER", Some("FLOWER"), 'C').unwrap();
+            for p in 0..26 {
+                for k in 0..26 {
+                    assert_eq!(quagmire.decrypt(quagmire.encrypt(p, k), k), p);
+                }
+            }
+            for shift in 0..26 {
+                assert_eq!(quagmire.shift(quagmire.key_letter(shift)), shift);
+            }
+        }
+        assert!(Quagmire::new(QuagmireKind::IV, "SPRINGFEVER", None, 'A').is_err());
+    }
+
+    #[test]
+    fn quagmire_iii_example() {
+        // key letter K slides the ciphertext alphabet until K sits under the indicator A, so A encrypts to K
+        let quagmire = Quagmire::new(QuagmireKind::III, "KRYPTOS", None, 'A').unwrap();
+        assert_eq!(quagmire.encrypt(0, 10), 10);
+        assert_eq!(quagmire.shift(10), 19);
+        assert_eq!(quagmire.plain_alphabet(), "KRYPTOSABCDEFGHIJLMNQUVWXZ");
+        assert_eq!(quagmire.cipher_alphabet(), "KRYPTOSABCDEFGHIJLMNQUVWXZ");
+        assert_eq!("iv".parse(), Ok(QuagmireKind::IV));
+        assert!("5".parse::<QuagmireKind>().is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ALPHABET_LEN;
+
+/*
+    The ways a key letter can be combined with a plaintext letter, all working on letters as
+    0..=25. Every one of them gives a fixed substitution alphabet per key letter, so a single
+    column of the ciphertext is still one alphabet and the same IOC, chi-squared and n-gram
+    cracking works for each.
+
+    https://en.wikipedia.org/wiki/Beaufort_cipher
+    https://en.wikipedia.org/wiki/Porta_cipher
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tableau {
+    #[default]
+    Vigenere,        // c = p + k
+    Beaufort,        // c = k - p, so encrypting and decrypting are the same operation
+    VariantBeaufort, // c = p - k, Vigenere decryption used to encrypt
+    Porta,           // 13 reciprocal alphabets swapping A-M with N-Z, key letters pair up as AB, CD, ...
+}
+
+impl Tableau {
+    pub const ALL: [Tableau; 4] = [Tableau::Vigenere, Tableau::Beaufort, Tableau::VariantBeaufort, Tableau::Porta];
+
+    #[inline]
+    pub fn encrypt(self, plain: u8, key: u8) -> u8 {
+        self.encrypt_in(plain, key, ALPHABET_LEN)
+    }
+
+    #[inline]
+    pub fn decrypt(self, cipher: u8, key: u8) -> u8 {
+        self.decrypt_in(cipher, key, ALPHABET_LEN)
+    }
+
+    // the same over an alphabet of size symbols (see Alphabet), Porta needs an even size to pair them up
+    #[inline]
+    pub fn encrypt_in(self, plain: u8, key: u8, size: usize) -> u8 {
+        let (plain, key) = (plain as usize, key as usize);
+        (match self {
+            Tableau::Vigenere => (plain + key) % size,
+            Tableau::Beaufort => (size + key - plain) % size,
+            Tableau::VariantBeaufort => (size + plain - key) % size,
+            Tableau::Porta => porta(plain, key, size / 2),
+        }) as u8
+    }
+
+    #[inline]
+    pub fn decrypt_in(self, cipher: u8, key: u8, size: usize) -> u8 {
+        let (cipher, key) = (cipher as usize, key as usize);
+        (match self {
+            Tableau::Vigenere => (size + cipher - key) % size,
+            Tableau::Beaufort => (size + key - cipher) % size,
+            Tableau::VariantBeaufort => (cipher + key) % size,
+            Tableau::Porta => porta(cipher, key, size / 2),
+        }) as u8
+    }
+
+    // whether the tableau works over an alphabet of size symbols
+    pub fn supports(self, size: usize) -> bool {
+        self != Tableau::Porta || size % 2 == 0
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tableau::Vigenere => "vigenere",
+            Tableau::Beaufort => "beaufort",
+            Tableau::VariantBeaufort => "variant-beaufort",
+            Tableau::Porta => "porta",
+        }
+    }
+}
+
+// the first half of the alphabet is shifted into the second by key / 2 and vice versa, so applying it twice undoes it
+fn porta(letter: usize, key: usize, half: usize) -> usize {
+    let shift = key / 2 % half;
+    if letter < half {
+        half + (letter + shift) % half
+    }
+    else {
+        (letter - shift) % half
+    }
+}
+
+impl fmt::Display for Tableau {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Tableau {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Tableau, String> {
+        match Tableau::ALL.iter().find(|tableau| tableau.name() == s.to_ascii_lowercase()) {
+            Some(tableau) => Ok(*tableau),
+            None => {
+                let names: Vec<&str> = Tableau::ALL.iter().map(|tableau| tableau.name()).collect();
+                Err(format!("unknown cipher {}, expected one of {}", s, names.join(", ")))
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tableaus_round_trip() {
+        for tableau in Tableau::ALL {
+            for p in 0..26 {
+                for k in 0..26 {
+                    assert_eq!(tableau.decrypt(tableau.encrypt(p, k), k), p);
+                }
+            }
+        }
+        // H with key S: Vigenere 7 + 18 = Z, Beaufort 18 - 7 = L, variant 7 - 18 = P
+        assert_eq!(Tableau::Vigenere.encrypt(7, 18), 25);
+        assert_eq!(Tableau::Beaufort.encrypt(7, 18), 11);
+        assert_eq!(Tableau::VariantBeaufort.encrypt(7, 18), 15);
+        assert_eq!("Variant-Beaufort".parse(), Ok(Tableau::VariantBeaufort));
+        // key A (or B) turns ABC...Z into NOP...ZABC...M, key Y (or Z) shifts both halves by 12 more
+        assert_eq!(Tableau::Porta.encrypt(0, 0), 13);
+        assert_eq!(Tableau::Porta.encrypt(0, 1), 13);
+        assert_eq!(Tableau::Porta.encrypt(0, 24), 25);
+        assert_eq!(Tableau::Porta.encrypt(25, 24), 0);
+        assert!("playfair".parse::<Tableau>().is_err());
+
+        // over printable ASCII, ~ with key ! (shift 1) wraps round to space
+        for tableau in Tableau::ALL.into_iter().filter(|tableau| tableau.supports(95)) {
+            for p in 0..95 {
+                assert_eq!(tableau.decrypt_in(tableau.encrypt_in(p, 60, 95), 60, 95), p);
+            }
+        }
+        assert_eq!(Tableau::Vigenere.encrypt_in(94, 1, 95), 0);
+        assert!(!Tableau::Porta.supports(95) && Tableau::Porta.supports(36));
+    }
+}
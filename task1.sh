@@ -1 +0,0 @@
-./target/release/encode
\ No newline at end of file
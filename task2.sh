@@ -1 +0,0 @@
-./target/release/decode_given_key
\ No newline at end of file
@@ -1 +0,0 @@
-./target/release/decode_given_length
\ No newline at end of file
@@ -1 +0,0 @@
-./target/release/decode
\ No newline at end of file
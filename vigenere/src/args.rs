@@ -0,0 +1,933 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use decode::anneal::{Annealing, Schedule};
+use decode::autokey::DEFAULT_PRIMER_LENGTHS;
+use decode::genetic::Evolution;
+use decode::{DEFAULT_CANDIDATES, DEFAULT_LENGTH_RANGE, DEFAULT_SEED};
+use keyphrase::{Alphabet, Language, QuagmireKind, Tableau, UNKNOWN_KEY_LETTER};
+
+pub const USAGE: &str = "Usage: vigenere <COMMAND> [OPTIONS]
+
+Commands:
+  encode    Encrypt plaintext with a known key
+  decrypt   Decrypt ciphertext with a known key
+  crack     Recover the key and plaintext from ciphertext
+  analyse   Rank likely key lengths and list Kasiski repeats without cracking
+  drag      Line known plaintext up at every position and show the key letters it gives
+
+Options:
+  -k, --key <KEY>          Key phrase made of letters A-Z, ? for any not known (encode, decrypt, crack)
+      --cipher <NAME>      vigenere (default), beaufort, variant-beaufort or porta (encode, decrypt, crack)
+      --autokey            Use --key as a primer followed by the plaintext itself (encode, decrypt, crack)
+      --gronsfeld          The key is digits 0-9 instead of letters (encode, decrypt, crack)
+      --running-key <FILE> Use the letters of FILE as the key, - for stdin (encode, decrypt, crack)
+      --offset <N>         Start the running key N letters into FILE, default 0 (encode, decrypt)
+      --progressive        Move every shift of --key on after each pass through it (encode, decrypt, crack)
+      --step <N>           How far --progressive moves the shifts each time, default 1 (encode, decrypt, crack)
+      --trithemius         Shift each letter one further than the last, no key needed (encode, decrypt)
+      --quagmire <1-4>     Use Quagmire I, II, III or IV keyed alphabets (encode, decrypt, crack)
+      --alphabet <WORD>    Keyword for the Quagmire keyed alphabet (encode, decrypt)
+      --cipher-alphabet <WORD>
+                           Keyword for the Quagmire IV ciphertext alphabet (encode, decrypt)
+      --indicator <LETTER> Plaintext letter the key letters are lined up under, default A (encode, decrypt)
+      --charset <SET>      Encipher letters (default), alphanumeric (A-Z0-9), printable (all 95 printable
+                           ASCII characters), russian, greek or the symbols given, in order
+                           (encode, decrypt, crack)
+  -l, --length <N>         Assume the key is N letters long (crack, drag)
+  -c, --candidates <N>     Crack the N best ranked key lengths and keep the best plaintext (crack)
+      --min-length <N>     Shortest key length to rank, default 1 (crack, analyse)
+      --max-length <N>     Longest key length to rank, default 40 (crack, analyse)
+      --anneal             Find the key by simulated annealing instead of hill climbing (crack)
+      --schedule <NAME>    How --anneal cools: geometric (default) or linear (crack)
+      --temperature <T>    Temperature --anneal starts at, default 4, cooling to a hundredth of it (crack)
+      --iterations <N>     Letters --anneal tries changing, default 20000 (crack)
+      --restarts <N>       Also hill climb from N random keys and keep the best key found (crack)
+      --genetic            Find the key by evolving a population of keys, for long keys (crack)
+      --population <N>     Keys in each --genetic generation, default 100 (crack)
+      --generations <N>    Generations --genetic breeds, default 300 (crack)
+      --mutation-rate <P>  Chance of --genetic changing each letter of a new key, default 0.02 (crack)
+      --time-limit <SECS>  Stop --genetic after SECS seconds even if generations remain (crack)
+      --seed <N>           Seed for the random choices of --anneal, --restarts or --genetic, the same
+                           seed gives the same key (crack)
+  -m, --model <FILE>       Score with a custom \"NGRAM COUNT\" model instead of the built-in (crack)
+      --lang <NAME>        Plaintext language: english (default), german, french, spanish, italian,
+                           portuguese or russian, by name or two letter code, or auto to detect it
+                           (crack, analyse)
+      --crib <TEXT>        A word or phrase known to be in the plaintext (drag)
+      --position <N>       Show the whole decryption with the crib N letters in (drag)
+  -i, --input <FILE>       Read the text from FILE instead of stdin
+  -o, --output <FILE>      Write the result to FILE instead of stdout
+  -h, --help               Print this help
+
+Without --length, crack takes the first key length whose index of coincidence looks like
+English. Giving --candidates, --min-length or --max-length switches to ranking every length
+in the range and cracking the best 3 (or N). With --autokey, crack tries every primer length
+from --min-length to --max-length (default 1 to 20, or exactly --length) and keeps the best.
+With --running-key, crack tries every offset into FILE and prints the best one instead of a key.
+With --progressive, crack tries every step (or just --step) at each key length before solving.
+With --quagmire, crack searches for the keyed alphabets as well and prints them after the key.
+With --charset, the key is made of the same symbols as the text and crack only takes --length.
+With --lang, letter frequencies, the index of coincidence and the quadgram model are those of
+that language. Russian has no quadgram model and is cracked with --charset russian.
+decrypt and crack take a --key with ? for the letters not known, e.g. SEC?R?TY. decrypt writes
+_ for the letters under them, crack keeps the rest of the key and searches only for those.
+drag needs --crib and --length and lists every position where the crib's key letters agree,
+with the key so far (? where unknown) and the start of the text it decrypts, _ where unknown.
+With --position, it prints that key and the whole decryption instead.
+With --anneal, crack starts from the same key as usual but will take worse keys for a while to
+get past ones that no single letter change improves. It only takes --length, --model, --lang and
+--cipher besides its own options.
+With --restarts, crack lists the best keys the climbs found and how many ended on each, the
+more on the best key the more it can be trusted, then the best key and its plaintext. It takes
+the same options as --anneal.
+With --genetic, crack breeds keys from the usual starting key, each taking letters from two
+good keys, which puts right letters found in different keys together. It is meant for keys of
+20 or more letters, where each letter has little ciphertext to go on, and takes the same
+options as --anneal. With --time-limit the key found may differ from run to run.
+With --lang auto, crack tries every language and prints how well each fitted, then the best
+language, its key and the plaintext. It only takes --cipher. Only languages written in A-Z are
+tried, so never Russian, which has to be given with --lang russian --charset russian and is
+cracked on letter frequencies alone, as there is no n-gram model of Russian.
+
+Exit status:
+  0  success
+  1  the input or output file could not be read or written
+  2  invalid command line
+  3  invalid key or key length
+  4  the n-gram model is missing or corrupt
+  5  the ciphertext is too short to analyse
+  6  drag --position is not a position the crib fits at
+  7  the cipher isn't supported by the command or --charset, the alphabet is invalid or the
+     crib has no letters
+
+Examples:
+  vigenere encode --key SECURITY --input message.txt
+  vigenere decrypt -k SECURITY -i cipher.txt -o message.txt
+  vigenere crack --key 'SEC?R?TY' < cipher.txt
+  vigenere crack --length 8 < cipher.txt
+  vigenere crack --cipher beaufort < cipher.txt
+  vigenere encode --autokey --key QUEENLY < message.txt
+  vigenere crack --autokey --max-length 10 < cipher.txt
+  vigenere encode --gronsfeld --key 31415 < message.txt
+  vigenere decrypt --running-key book.txt --offset 1200 < cipher.txt
+  vigenere crack --running-key book.txt < cipher.txt
+  vigenere encode --progressive --step 3 --key SECURITY < message.txt
+  vigenere crack --progressive < cipher.txt
+  vigenere encode --quagmire 3 --alphabet KRYPTOS --key SECURITY < message.txt
+  vigenere crack --quagmire 2 --length 3 < cipher.txt
+  vigenere encode --charset printable --key 'k3y 5!' < message.txt
+  vigenere crack --charset russian --lang ru < cipher.txt
+  vigenere crack < cipher.txt
+  vigenere crack --candidates 5 --max-length 60 < cipher.txt
+  vigenere crack --anneal --iterations 50000 --seed 7 < cipher.txt
+  vigenere crack --restarts 50 --length 8 < cipher.txt
+  vigenere crack --genetic --length 32 --time-limit 10 < cipher.txt
+  vigenere analyse < cipher.txt
+  vigenere drag --crib 'attack at dawn' --length 8 < cipher.txt
+  vigenere crack --lang auto < cipher.txt
+  vigenere crack --lang de --model german_quadgrams.txt -i cipher.txt";
+
+// (short form, long form) of every option that takes a value
+const OPTIONS: &[(&str, &str)] = &[
+    ("-k", "--key"),
+    ("", "--cipher"),
+    ("", "--running-key"),
+    ("", "--offset"),
+    ("", "--step"),
+    ("", "--quagmire"),
+    ("", "--alphabet"),
+    ("", "--cipher-alphabet"),
+    ("", "--indicator"),
+    ("", "--charset"),
+    ("-l", "--length"),
+    ("-c", "--candidates"),
+    ("", "--min-length"),
+    ("", "--max-length"),
+    ("", "--crib"),
+    ("", "--position"),
+    ("", "--schedule"),
+    ("", "--temperature"),
+    ("", "--iterations"),
+    ("", "--restarts"),
+    ("", "--population"),
+    ("", "--generations"),
+    ("", "--mutation-rate"),
+    ("", "--time-limit"),
+    ("", "--seed"),
+    ("-m", "--model"),
+    ("", "--lang"),
+    ("-i", "--input"),
+    ("-o", "--output"),
+];
+
+// options that take no value
+const FLAGS: &[&str] = &["--autokey", "--gronsfeld", "--progressive", "--trithemius", "--anneal", "--genetic"];
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Encode { key: Key, tableau: Tableau, alphabet: Alphabet },
+    Decrypt { key: Key, tableau: Tableau, alphabet: Alphabet },
+    Crack { length: Option<usize>, model: Option<PathBuf>, language: Language, ranking: Option<Ranking>, tableau: Tableau },
+    CrackPartial { key: String, model: Option<PathBuf>, language: Language, tableau: Tableau }, // --key with ? for unknown letters
+    CrackAnnealed { length: Option<usize>, model: Option<PathBuf>, language: Language, tableau: Tableau, annealing: Annealing },
+    CrackRestarts { length: Option<usize>, model: Option<PathBuf>, language: Language, tableau: Tableau, restarts: usize, seed: u64 },
+    CrackGenetic { length: Option<usize>, model: Option<PathBuf>, language: Language, tableau: Tableau, evolution: Evolution },
+    CrackGronsfeld { length: Option<usize>, model: Option<PathBuf>, language: Language, tableau: Tableau },
+    CrackAutokey { primer_lengths: RangeInclusive<usize>, model: Option<PathBuf>, language: Language, tableau: Tableau },
+    CrackRunningKey { key_text: PathBuf, model: Option<PathBuf>, language: Language, tableau: Tableau },
+    CrackProgressive { length: Option<usize>, step: Option<u8>, model: Option<PathBuf>, language: Language, tableau: Tableau },
+    CrackQuagmire { kind: QuagmireKind, length: Option<usize>, model: Option<PathBuf>, language: Language },
+    CrackAnyLanguage { tableau: Tableau }, // --lang auto
+    CrackAlphabet { length: Option<usize>, language: Language, tableau: Tableau, alphabet: Alphabet }, // any --charset but letters
+    Analyse { lengths: RangeInclusive<usize>, language: Language },
+    Drag { crib: String, length: usize, position: Option<usize>, tableau: Tableau },
+    Help,
+}
+
+// the key given to encode or decrypt and how it is turned into a key stream
+#[derive(Debug, PartialEq)]
+pub enum Key {
+    Repeating(String),
+    Partial(String), // with ? for unknown letters, only to decrypt over A-Z
+    Autokey(String), // the primer
+    Gronsfeld(String), // digits 0-9
+    Running { text: PathBuf, offset: usize }, // "-" reads the key text from stdin
+    Progressive { key: String, step: u8 }, // Trithemius is key A (the first symbol of --charset) with step 1
+    Quagmire(QuagmireKey),
+}
+
+// the periodic key and what builds the Quagmire alphabets, cipher_keyword only for IV
+#[derive(Debug, PartialEq)]
+pub struct QuagmireKey {
+    pub kind: QuagmireKind,
+    pub key: String,
+    pub keyword: String,
+    pub cipher_keyword: Option<String>,
+    pub indicator: char,
+}
+
+// crack by trying the best few lengths from decode::rank_key_lengths
+#[derive(Debug, PartialEq)]
+pub struct Ranking {
+    pub lengths: RangeInclusive<usize>,
+    pub candidates: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+}
+
+// parses everything after the program name, e.g. ["crack", "--length", "8"]
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+    let mut args = args.into_iter();
+
+    let subcommand = match args.next() {
+        Some(s) => s,
+        None => return Err(String::from("no command given")),
+    };
+    if subcommand == "-h" || subcommand == "--help" {
+        return Ok(Args { command: Command::Help, input: None, output: None });
+    }
+
+    // long option name -> value
+    let mut values: HashMap<&'static str, String> = HashMap::new();
+
+    while let Some(flag) = args.next() {
+        // accept both "--key SECURITY" and "--key=SECURITY"
+        let (name, inline_value) = match flag.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (flag.clone(), None),
+        };
+
+        if name == "-h" || name == "--help" {
+            return Ok(Args { command: Command::Help, input: None, output: None });
+        }
+        if let Some(flag) = FLAGS.iter().find(|flag| name == **flag) {
+            if inline_value.is_some() {
+                return Err(format!("{} does not take a value", name));
+            }
+            values.insert(flag, String::new());
+            continue;
+        }
+
+        let long = match OPTIONS.iter().find(|(short, long)| name == *long || (!short.is_empty() && name == *short)) {
+            Some((_, long)) => *long,
+            None => return Err(format!("unknown option: {}", name)),
+        };
+        let value = match inline_value.or_else(|| args.next()) {
+            Some(value) => value,
+            None => return Err(format!("missing value for {}", name)),
+        };
+        values.insert(long, value);
+    }
+
+    // --input and --output are accepted by every command
+    let allowed: &[&str] = match subcommand.as_str() {
+        "encode" | "decrypt" => &[
+            "--key", "--cipher", "--autokey", "--gronsfeld", "--running-key", "--offset", "--progressive", "--step", "--trithemius", "--quagmire", "--alphabet",
+            "--cipher-alphabet", "--indicator", "--charset",
+        ],
+        "crack" => &[
+            "--key", "--length", "--candidates", "--min-length", "--max-length", "--model", "--cipher", "--autokey", "--gronsfeld", "--running-key", "--progressive", "--step",
+            "--quagmire", "--charset", "--lang", "--anneal", "--schedule", "--temperature", "--iterations", "--restarts",
+            "--genetic", "--population", "--generations", "--mutation-rate", "--time-limit", "--seed",
+        ],
+        "analyse" => &["--min-length", "--max-length", "--lang"],
+        "drag" => &["--crib", "--length", "--position", "--cipher"],
+        _ => return Err(format!("unknown command: {}", subcommand)),
+    };
+    let input = values.remove("--input").map(PathBuf::from);
+    let output = values.remove("--output").map(PathBuf::from);
+    if let Some(name) = values.keys().find(|name| !allowed.contains(name)) {
+        return Err(format!("{} is not accepted by {}", name, subcommand));
+    }
+
+    let quagmire: Option<QuagmireKind> = values.remove("--quagmire").map(|kind| kind.parse()).transpose()?;
+    if quagmire.is_some() && values.contains_key("--cipher") {
+        return Err(String::from("--quagmire cannot be combined with --cipher"));
+    }
+    let tableau = match values.remove("--cipher") {
+        Some(name) => name.parse()?,
+        None => Tableau::Vigenere,
+    };
+    let charset = values.remove("--charset");
+    if charset.is_some() && quagmire.is_some() {
+        return Err(String::from("--quagmire cannot be combined with --charset"));
+    }
+    let alphabet: Alphabet = match &charset {
+        Some(charset) => charset.parse().map_err(|e| format!("{}", e))?,
+        None => Alphabet::letters(),
+    };
+    let lang = values.remove("--lang");
+    let detect = lang.as_deref().is_some_and(|name| name.eq_ignore_ascii_case("auto"));
+    let language: Language = match lang {
+        Some(name) if !detect => name.parse().map_err(|e| format!("{}", e))?,
+        _ => Language::default(),
+    };
+    let autokey = values.remove("--autokey").is_some();
+    let running_key = values.remove("--running-key").map(PathBuf::from);
+    if running_key.as_deref() == Some(Path::new("-")) && input.is_none() {
+        return Err(String::from("--running-key - needs the text itself from --input"));
+    }
+    let gronsfeld = values.remove("--gronsfeld").is_some();
+    let progressive = values.remove("--progressive").is_some();
+    let trithemius = values.remove("--trithemius").is_some();
+    if [autokey, gronsfeld, running_key.is_some(), progressive, trithemius, quagmire.is_some()].iter().filter(|&&set| set).count() > 1 {
+        return Err(String::from("only one of --autokey, --gronsfeld, --running-key, --progressive, --trithemius and --quagmire can be given"));
+    }
+    let step = match parse_number(&values, "--step")? {
+        Some(_) if !progressive => return Err(String::from("--step needs --progressive")),
+        Some(step) => Some((step % alphabet.len()) as u8),
+        None => None,
+    };
+    if quagmire.is_none() {
+        if let Some(name) = ["--alphabet", "--cipher-alphabet", "--indicator"].iter().find(|name| values.contains_key(*name)) {
+            return Err(format!("{} needs --quagmire", name));
+        }
+    }
+
+    let command = match subcommand.as_str() {
+        "encode" | "decrypt" => {
+            let offset = parse_number(&values, "--offset")?;
+            let key = match (values.remove("--key"), running_key) {
+                (Some(_), Some(_)) => return Err(String::from("--key cannot be combined with --running-key")),
+                (None, Some(text)) => Key::Running { text, offset: offset.unwrap_or(0) },
+                (Some(_), None) if trithemius => return Err(String::from("--trithemius does not take a --key")),
+                (None, None) if trithemius => Key::Progressive { key: alphabet.symbol(0).to_string(), step: 1 },
+                (_, None) if offset.is_some() => return Err(String::from("--offset needs --running-key")),
+                (Some(key), None) if autokey => Key::Autokey(key),
+                (Some(key), None) if gronsfeld => Key::Gronsfeld(key),
+                (Some(key), None) if progressive => Key::Progressive { key, step: step.unwrap_or(1) },
+                // ? is a symbol of its own in --charset printable
+                (Some(key), None) if subcommand == "decrypt" && quagmire.is_none() && alphabet == Alphabet::letters() && key.contains(UNKNOWN_KEY_LETTER) => {
+                    Key::Partial(key)
+                }
+                (Some(key), None) => match quagmire {
+                    Some(kind) => Key::Quagmire(quagmire_key(&mut values, kind, key)?),
+                    None => Key::Repeating(key),
+                },
+                (None, None) => return Err(format!("{} requires --key or --running-key", subcommand)),
+            };
+            if subcommand == "encode" {
+                Command::Encode { key, tableau, alphabet }
+            }
+            else {
+                Command::Decrypt { key, tableau, alphabet }
+            }
+        }
+        "crack" => {
+            let length = parse_number(&values, "--length")?;
+            let candidates = parse_number(&values, "--candidates")?;
+            let min_length = parse_number(&values, "--min-length")?;
+            let max_length = parse_number(&values, "--max-length")?;
+            let model = values.remove("--model").map(PathBuf::from);
+            let restarts = parse_number(&values, "--restarts")?;
+            let seed: Option<u64> = match values.remove("--seed") {
+                Some(seed) => Some(seed.parse().map_err(|_| format!("invalid number for --seed: {}", seed))?),
+                None => None,
+            };
+            let annealing = annealing(&mut values, seed)?;
+            let evolution = evolution(&mut values, seed)?;
+            let searches = [annealing.is_some(), restarts.is_some(), evolution.is_some()].iter().filter(|&&given| given).count();
+            if seed.is_some() && searches == 0 {
+                return Err(String::from("--seed needs --anneal, --restarts or --genetic"));
+            }
+
+            if !language.is_latin() && alphabet == Alphabet::letters() {
+                return Err(format!("--lang {} needs --charset with its letters, e.g. --charset {}", language, language));
+            }
+            if let Some(key) = values.remove("--key") {
+                if detect || alphabet != Alphabet::letters() || running_key.is_some() || autokey || gronsfeld || progressive || quagmire.is_some() || searches > 0 {
+                    return Err(String::from("crack --key only works on a repeating key over letters, without --anneal, --restarts or --genetic"));
+                }
+                if length.is_some() || candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--key cannot be combined with key length options, the key's own length is used"));
+                }
+                return Ok(Args { command: Command::CrackPartial { key, model, language, tableau }, input, output });
+            }
+            if searches > 0 {
+                if detect || alphabet != Alphabet::letters() || running_key.is_some() || autokey || gronsfeld || progressive || quagmire.is_some() {
+                    return Err(String::from("--anneal, --restarts and --genetic only crack a repeating key over letters in a given language"));
+                }
+                if candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--anneal, --restarts and --genetic cannot be combined with --candidates, --min-length or --max-length"));
+                }
+                let command = match (annealing, restarts, evolution) {
+                    (Some(annealing), None, None) => Command::CrackAnnealed { length, model, language, tableau, annealing },
+                    (None, Some(restarts), None) => Command::CrackRestarts { length, model, language, tableau, restarts, seed: seed.unwrap_or(DEFAULT_SEED) },
+                    (None, None, Some(evolution)) => Command::CrackGenetic { length, model, language, tableau, evolution },
+                    _ => return Err(String::from("only one of --anneal, --restarts and --genetic can be given")),
+                };
+                return Ok(Args { command, input, output });
+            }
+            if detect {
+                if alphabet != Alphabet::letters() || running_key.is_some() || autokey || gronsfeld || progressive || quagmire.is_some() {
+                    return Err(String::from("--lang auto can only crack a repeating key over letters"));
+                }
+                if length.is_some() || candidates.is_some() || min_length.is_some() || max_length.is_some() || model.is_some() {
+                    return Err(String::from("--lang auto cannot be combined with key length options or --model"));
+                }
+                return Ok(Args { command: Command::CrackAnyLanguage { tableau }, input, output });
+            }
+            if alphabet != Alphabet::letters() {
+                if running_key.is_some() || autokey || gronsfeld || progressive {
+                    return Err(String::from("--charset can only be cracked as a repeating key"));
+                }
+                if candidates.is_some() || min_length.is_some() || max_length.is_some() || model.is_some() {
+                    return Err(String::from("--charset cannot be combined with --candidates, --min-length, --max-length or --model"));
+                }
+                return Ok(Args { command: Command::CrackAlphabet { length, language, tableau, alphabet }, input, output });
+            }
+            if let Some(key_text) = running_key {
+                if length.is_some() || candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--running-key cannot be combined with key length options"));
+                }
+                return Ok(Args { command: Command::CrackRunningKey { key_text, model, language, tableau }, input, output });
+            }
+            if progressive {
+                if candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--progressive cannot be combined with --candidates, --min-length or --max-length"));
+                }
+                if tableau == Tableau::Porta {
+                    return Err(String::from("--progressive cannot be cracked with --cipher porta"));
+                }
+                return Ok(Args { command: Command::CrackProgressive { length, step, model, language, tableau }, input, output });
+            }
+            if let Some(kind) = quagmire {
+                if candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--quagmire cannot be combined with --candidates, --min-length or --max-length"));
+                }
+                return Ok(Args { command: Command::CrackQuagmire { kind, length, model, language }, input, output });
+            }
+            if gronsfeld {
+                if candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                    return Err(String::from("--gronsfeld cannot be combined with --candidates, --min-length or --max-length"));
+                }
+                return Ok(Args { command: Command::CrackGronsfeld { length, model, language, tableau }, input, output });
+            }
+            if autokey {
+                if candidates.is_some() {
+                    return Err(String::from("--candidates cannot be combined with --autokey"));
+                }
+                let primer_lengths = match length {
+                    Some(length) if min_length.is_none() && max_length.is_none() => length_range(Some(length), Some(length), DEFAULT_PRIMER_LENGTHS)?,
+                    Some(_) => return Err(String::from("--length cannot be combined with --min-length or --max-length")),
+                    None => length_range(min_length, max_length, DEFAULT_PRIMER_LENGTHS)?,
+                };
+                return Ok(Args { command: Command::CrackAutokey { primer_lengths, model, language, tableau }, input, output });
+            }
+
+            let ranking = if candidates.is_some() || min_length.is_some() || max_length.is_some() {
+                if length.is_some() {
+                    return Err(String::from("--length cannot be combined with --candidates, --min-length or --max-length"));
+                }
+                Some(Ranking {
+                    lengths: length_range(min_length, max_length, DEFAULT_LENGTH_RANGE)?,
+                    candidates: candidates.unwrap_or(DEFAULT_CANDIDATES),
+                })
+            }
+            else {
+                None
+            };
+            Command::Crack { length, model, language, ranking, tableau }
+        }
+        "drag" => {
+            let length = match parse_number(&values, "--length")? {
+                Some(length) if length > 0 => length,
+                Some(_) => return Err(String::from("--length must be at least 1")),
+                None => return Err(String::from("drag requires --length")),
+            };
+            let position = parse_number(&values, "--position")?;
+            let crib = match values.remove("--crib") {
+                Some(crib) => crib,
+                None => return Err(String::from("drag requires --crib")),
+            };
+            Command::Drag { crib, length, position, tableau }
+        }
+        _ => {
+            if detect {
+                return Err(String::from("--lang auto is only accepted by crack"));
+            }
+            if !language.is_latin() {
+                return Err(format!("analyse only works on A-Z, which --lang {} is not written in", language));
+            }
+            let min_length = parse_number(&values, "--min-length")?;
+            let max_length = parse_number(&values, "--max-length")?;
+            Command::Analyse { lengths: length_range(min_length, max_length, DEFAULT_LENGTH_RANGE)?, language }
+        }
+    };
+
+    Ok(Args { command, input, output })
+}
+
+// the --anneal settings, None without --anneal, which the other settings need
+fn annealing(values: &mut HashMap<&'static str, String>, seed: Option<u64>) -> Result<Option<Annealing>, String> {
+    let anneal = values.remove("--anneal").is_some();
+    if !anneal {
+        return match ["--schedule", "--temperature", "--iterations"].iter().find(|name| values.contains_key(*name)) {
+            Some(name) => Err(format!("{} needs --anneal", name)),
+            None => Ok(None),
+        };
+    }
+    let mut annealing = Annealing::default();
+    if let Some(schedule) = values.remove("--schedule") {
+        annealing.schedule = schedule.parse::<Schedule>()?;
+    }
+    if let Some(temperature) = values.remove("--temperature") {
+        annealing.start_temperature = match temperature.parse::<f64>() {
+            Ok(t) if t > 0.0 && t.is_finite() => t,
+            _ => return Err(format!("invalid number for --temperature: {}", temperature)),
+        };
+        annealing.end_temperature = annealing.start_temperature / 100.0;
+    }
+    if let Some(iterations) = parse_number(values, "--iterations")? {
+        annealing.iterations = iterations;
+    }
+    if let Some(seed) = seed {
+        annealing.seed = seed;
+    }
+    Ok(Some(annealing))
+}
+
+// the --genetic settings, None without --genetic, which the other settings need
+fn evolution(values: &mut HashMap<&'static str, String>, seed: Option<u64>) -> Result<Option<Evolution>, String> {
+    let genetic = values.remove("--genetic").is_some();
+    if !genetic {
+        return match ["--population", "--generations", "--mutation-rate", "--time-limit"].iter().find(|name| values.contains_key(*name)) {
+            Some(name) => Err(format!("{} needs --genetic", name)),
+            None => Ok(None),
+        };
+    }
+    let mut evolution = Evolution::default();
+    if let Some(population) = parse_number(values, "--population")? {
+        if population < 2 {
+            return Err(format!("invalid number for --population: {}, at least 2 keys are needed to breed", population));
+        }
+        evolution.population = population;
+    }
+    if let Some(generations) = parse_number(values, "--generations")? {
+        evolution.generations = generations;
+    }
+    if let Some(rate) = values.remove("--mutation-rate") {
+        evolution.mutation_rate = match rate.parse::<f64>() {
+            Ok(p) if (0.0..=1.0).contains(&p) => p,
+            _ => return Err(format!("invalid probability for --mutation-rate: {}, expected 0 to 1", rate)),
+        };
+    }
+    if let Some(limit) = values.remove("--time-limit") {
+        evolution.time_budget = match limit.parse::<f64>() {
+            Ok(secs) if secs > 0.0 && secs.is_finite() => Some(Duration::from_secs_f64(secs)),
+            _ => return Err(format!("invalid number for --time-limit: {}", limit)),
+        };
+    }
+    if let Some(seed) = seed {
+        evolution.seed = seed;
+    }
+    Ok(Some(evolution))
+}
+
+// --alphabet is required, --cipher-alphabet only and always for type IV
+fn quagmire_key(values: &mut HashMap<&'static str, String>, kind: QuagmireKind, key: String) -> Result<QuagmireKey, String> {
+    let keyword = match values.remove("--alphabet") {
+        Some(keyword) => keyword,
+        None => return Err(String::from("--quagmire requires --alphabet")),
+    };
+    let cipher_keyword = values.remove("--cipher-alphabet");
+    match (kind, &cipher_keyword) {
+        (QuagmireKind::IV, None) => return Err(String::from("--quagmire 4 requires --cipher-alphabet")),
+        (QuagmireKind::IV, Some(_)) | (_, None) => {}
+        (_, Some(_)) => return Err(String::from("--cipher-alphabet is only used by --quagmire 4")),
+    }
+    let indicator = match values.remove("--indicator") {
+        None => 'A',
+        Some(indicator) => {
+            let mut chars = indicator.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => c,
+                _ => return Err(format!("invalid indicator {}, expected a single letter", indicator)),
+            }
+        }
+    };
+    Ok(QuagmireKey { kind, key, keyword, cipher_keyword, indicator })
+}
+
+fn parse_number(values: &HashMap<&'static str, String>, name: &str) -> Result<Option<usize>, String> {
+    match values.get(name) {
+        Some(value) => match value.parse() {
+            Ok(n) => Ok(Some(n)),
+            Err(_) => Err(format!("invalid number for {}: {}", name, value)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn length_range(min_length: Option<usize>, max_length: Option<usize>, default: RangeInclusive<usize>) -> Result<RangeInclusive<usize>, String> {
+    let min_length = min_length.unwrap_or(*default.start());
+    let max_length = max_length.unwrap_or(*default.end());
+    if min_length == 0 || min_length > max_length {
+        return Err(format!("invalid key length range {}..={}", min_length, max_length));
+    }
+    Ok(min_length..=max_length)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Args, String> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_encode() {
+        let args = parse("encode --key SECURITY -i in.txt --output=out.txt").unwrap();
+        assert_eq!(args.command, Command::Encode { key: Key::Repeating(String::from("SECURITY")), tableau: Tableau::Vigenere, alphabet: Alphabet::letters() });
+        assert_eq!(args.input, Some(PathBuf::from("in.txt")));
+        assert_eq!(args.output, Some(PathBuf::from("out.txt")));
+
+        let args = parse("decrypt -k SECURITY --cipher variant-beaufort").unwrap();
+        assert_eq!(args.command, Command::Decrypt { key: Key::Repeating(String::from("SECURITY")), tableau: Tableau::VariantBeaufort, alphabet: Alphabet::letters() });
+    }
+
+    #[test]
+    fn parses_crack() {
+        assert_eq!(parse("crack").unwrap().command, Command::Crack { length: None, model: None, language: Language::English, ranking: None, tableau: Tableau::Vigenere });
+        assert_eq!(parse("crack -l 8").unwrap().command, Command::Crack { length: Some(8), model: None, language: Language::English, ranking: None, tableau: Tableau::Vigenere });
+        assert_eq!(
+            parse("crack --model german.txt").unwrap().command,
+            Command::Crack { length: None, model: Some(PathBuf::from("german.txt")), language: Language::English, ranking: None, tableau: Tableau::Vigenere }
+        );
+        assert_eq!(
+            parse("crack --max-length 60").unwrap().command,
+            Command::Crack { length: None, model: None, language: Language::English, ranking: Some(Ranking { lengths: 1..=60, candidates: 3 }), tableau: Tableau::Vigenere }
+        );
+        assert_eq!(
+            parse("crack -c 5 --min-length 2").unwrap().command,
+            Command::Crack { length: None, model: None, language: Language::English, ranking: Some(Ranking { lengths: 2..=40, candidates: 5 }), tableau: Tableau::Vigenere }
+        );
+    }
+
+    #[test]
+    fn parses_partial_key() {
+        let args = parse("decrypt -k SEC?R?TY --cipher beaufort").unwrap();
+        assert_eq!(args.command, Command::Decrypt { key: Key::Partial(String::from("SEC?R?TY")), tableau: Tableau::Beaufort, alphabet: Alphabet::letters() });
+        // not a wildcard when ? is in the charset
+        let args = parse("decrypt -k SEC?R?TY --charset printable").unwrap();
+        assert!(matches!(args.command, Command::Decrypt { key: Key::Repeating(_), .. }));
+        assert_eq!(
+            parse("crack --key SEC?R?TY --lang de").unwrap().command,
+            Command::CrackPartial { key: String::from("SEC?R?TY"), model: None, language: Language::German, tableau: Tableau::Vigenere }
+        );
+        assert!(parse("crack --key SEC?R?TY -l 8").is_err());
+        assert!(parse("crack --key SEC?R?TY --autokey").is_err());
+        assert!(parse("crack --key SEC?R?TY --lang auto").is_err());
+    }
+
+    #[test]
+    fn parses_anneal() {
+        let command = |annealing| Command::CrackAnnealed { length: Some(8), model: None, language: Language::English, tableau: Tableau::Vigenere, annealing };
+        assert_eq!(parse("crack --anneal -l 8").unwrap().command, command(Annealing::default()));
+        let annealing = Annealing { schedule: Schedule::Linear, start_temperature: 2.0, end_temperature: 0.02, iterations: 500, seed: 7 };
+        assert_eq!(parse("crack --anneal -l 8 --schedule linear --temperature 2 --iterations 500 --seed 7").unwrap().command, command(annealing));
+        assert!(parse("crack --seed 7").is_err());
+        assert!(parse("crack --anneal --temperature -1").is_err());
+        assert!(parse("crack --anneal --autokey").is_err());
+        assert!(parse("crack --anneal --candidates 3").is_err());
+        assert!(parse("crack --anneal --restarts 5").is_err());
+    }
+
+    #[test]
+    fn parses_genetic() {
+        let command = |evolution| Command::CrackGenetic { length: Some(32), model: None, language: Language::English, tableau: Tableau::Vigenere, evolution };
+        assert_eq!(parse("crack --genetic -l 32").unwrap().command, command(Evolution::default()));
+        let evolution = Evolution { population: 50, generations: 1000, mutation_rate: 0.05, time_budget: Some(Duration::from_millis(2500)), seed: 7, ..Evolution::default() };
+        assert_eq!(
+            parse("crack --genetic -l 32 --population 50 --generations 1000 --mutation-rate 0.05 --time-limit 2.5 --seed 7").unwrap().command,
+            command(evolution)
+        );
+        assert!(parse("crack --generations 10").is_err());
+        assert!(parse("crack --genetic --population 1").is_err());
+        assert!(parse("crack --genetic --mutation-rate 2").is_err());
+        assert!(parse("crack --genetic --time-limit 0").is_err());
+        assert!(parse("crack --genetic --anneal").is_err());
+        assert!(parse("crack --genetic --quagmire 1").is_err());
+    }
+
+    #[test]
+    fn parses_restarts() {
+        assert_eq!(
+            parse("crack --restarts 50 --seed 7 --cipher porta").unwrap().command,
+            Command::CrackRestarts { length: None, model: None, language: Language::English, tableau: Tableau::Porta, restarts: 50, seed: 7 }
+        );
+        assert_eq!(
+            parse("crack --restarts 5 -l 8").unwrap().command,
+            Command::CrackRestarts { length: Some(8), model: None, language: Language::English, tableau: Tableau::Vigenere, restarts: 5, seed: DEFAULT_SEED }
+        );
+        assert!(parse("crack --restarts five").is_err());
+        assert!(parse("crack --restarts 5 --gronsfeld").is_err());
+    }
+
+    #[test]
+    fn parses_autokey() {
+        let args = parse("encode --autokey --key QUEENLY").unwrap();
+        assert_eq!(args.command, Command::Encode { key: Key::Autokey(String::from("QUEENLY")), tableau: Tableau::Vigenere, alphabet: Alphabet::letters() });
+        assert_eq!(
+            parse("crack --autokey").unwrap().command,
+            Command::CrackAutokey { primer_lengths: 1..=20, model: None, language: Language::English, tableau: Tableau::Vigenere }
+        );
+        assert_eq!(
+            parse("crack --autokey -l 7 --cipher beaufort").unwrap().command,
+            Command::CrackAutokey { primer_lengths: 7..=7, model: None, language: Language::English, tableau: Tableau::Beaufort }
+        );
+        assert!(parse("crack --autokey --candidates 3").is_err());
+        assert!(parse("crack --autokey=yes").is_err());
+        assert!(parse("analyse --autokey").is_err());
+    }
+
+    #[test]
+    fn parses_running_key() {
+        assert_eq!(
+            parse("decrypt --running-key book.txt --offset 1200").unwrap().command,
+            Command::Decrypt { key: Key::Running { text: PathBuf::from("book.txt"), offset: 1200 }, tableau: Tableau::Vigenere, alphabet: Alphabet::letters() }
+        );
+        assert_eq!(
+            parse("encode --running-key - -i message.txt").unwrap().command,
+            Command::Encode { key: Key::Running { text: PathBuf::from("-"), offset: 0 }, tableau: Tableau::Vigenere, alphabet: Alphabet::letters() }
+        );
+        assert_eq!(
+            parse("crack --running-key book.txt").unwrap().command,
+            Command::CrackRunningKey { key_text: PathBuf::from("book.txt"), model: None, language: Language::English, tableau: Tableau::Vigenere }
+        );
+        assert!(parse("encode --running-key -").is_err());
+        assert!(parse("encode --key SECURITY --running-key book.txt").is_err());
+        assert!(parse("encode --key SECURITY --offset 3").is_err());
+        assert!(parse("decrypt --autokey --running-key book.txt").is_err());
+        assert!(parse("crack --running-key book.txt --length 8").is_err());
+        assert!(parse("crack --running-key book.txt --offset 8").is_err());
+    }
+
+    #[test]
+    fn parses_gronsfeld() {
+        assert_eq!(
+            parse("encode --gronsfeld -k 31415").unwrap().command,
+            Command::Encode { key: Key::Gronsfeld(String::from("31415")), tableau: Tableau::Vigenere, alphabet: Alphabet::letters() }
+        );
+        assert_eq!(
+            parse("crack --gronsfeld --length 5").unwrap().command,
+            Command::CrackGronsfeld { length: Some(5), model: None, language: Language::English, tableau: Tableau::Vigenere }
+        );
+        assert!(parse("crack --gronsfeld --autokey").is_err());
+        assert!(parse("crack --gronsfeld --max-length 9").is_err());
+    }
+
+    #[test]
+    fn parses_progressive() {
+        assert_eq!(
+            parse("encode --progressive --step 3 -k SECURITY").unwrap().command,
+            Command::Encode { key: Key::Progressive { key: String::from("SECURITY"), step: 3 }, tableau: Tableau::Vigenere, alphabet: Alphabet::letters() }
+        );
+        assert_eq!(
+            parse("decrypt --trithemius --cipher beaufort").unwrap().command,
+            Command::Decrypt { key: Key::Progressive { key: String::from("A"), step: 1 }, tableau: Tableau::Beaufort, alphabet: Alphabet::letters() }
+        );
+        assert_eq!(
+            parse("crack --progressive").unwrap().command,
+            Command::CrackProgressive { length: None, step: None, model: None, language: Language::English, tableau: Tableau::Vigenere }
+        );
+        assert_eq!(
+            parse("crack --progressive --step 28 -l 7").unwrap().command,
+            Command::CrackProgressive { length: Some(7), step: Some(2), model: None, language: Language::English, tableau: Tableau::Vigenere }
+        );
+        assert!(parse("encode --step 3 -k SECURITY").is_err());
+        assert!(parse("encode --trithemius -k SECURITY").is_err());
+        assert!(parse("encode --progressive --autokey -k SECURITY").is_err());
+        assert!(parse("crack --trithemius").is_err());
+        assert!(parse("crack --progressive --cipher porta").is_err());
+        assert!(parse("crack --progressive --max-length 9").is_err());
+    }
+
+    #[test]
+    fn parses_quagmire() {
+        assert_eq!(
+            parse("encode --quagmire 3 --alphabet KRYPTOS -k SECURITY").unwrap().command,
+            Command::Encode {
+                key: Key::Quagmire(QuagmireKey {
+                    kind: QuagmireKind::III,
+                    key: String::from("SECURITY"),
+                    keyword: String::from("KRYPTOS"),
+                    cipher_keyword: None,
+                    indicator: 'A',
+                }),
+                tableau: Tableau::Vigenere,
+                alphabet: Alphabet::letters(),
+            }
+        );
+        assert_eq!(
+            parse("decrypt --quagmire IV --alphabet KRYPTOS --cipher-alphabet FLOWER --indicator C -k SECURITY").unwrap().command,
+            Command::Decrypt {
+                key: Key::Quagmire(QuagmireKey {
+                    kind: QuagmireKind::IV,
+                    key: String::from("SECURITY"),
+                    keyword: String::from("KRYPTOS"),
+                    cipher_keyword: Some(String::from("FLOWER")),
+                    indicator: 'C',
+                }),
+                tableau: Tableau::Vigenere,
+                alphabet: Alphabet::letters(),
+            }
+        );
+        assert_eq!(
+            parse("crack --quagmire 2 -l 3").unwrap().command,
+            Command::CrackQuagmire { kind: QuagmireKind::II, length: Some(3), model: None, language: Language::English }
+        );
+        assert!(parse("encode --quagmire 5 --alphabet KRYPTOS -k SECURITY").is_err());
+        assert!(parse("encode --quagmire 1 -k SECURITY").is_err());
+        assert!(parse("encode --quagmire 4 --alphabet KRYPTOS -k SECURITY").is_err());
+        assert!(parse("encode --quagmire 1 --alphabet KRYPTOS --cipher-alphabet FLOWER -k SECURITY").is_err());
+        assert!(parse("encode --quagmire 1 --alphabet KRYPTOS --indicator AB -k SECURITY").is_err());
+        assert!(parse("encode --alphabet KRYPTOS -k SECURITY").is_err());
+        assert!(parse("encode --quagmire 1 --alphabet KRYPTOS --cipher beaufort -k SECURITY").is_err());
+        assert!(parse("crack --quagmire 1 --autokey").is_err());
+        assert!(parse("crack --quagmire 1 --candidates 3").is_err());
+        assert!(parse("crack --quagmire 1 --alphabet KRYPTOS").is_err());
+    }
+
+    #[test]
+    fn parses_charset() {
+        assert_eq!(
+            parse("encode --charset alphanumeric -k K3Y").unwrap().command,
+            Command::Encode { key: Key::Repeating(String::from("K3Y")), tableau: Tableau::Vigenere, alphabet: Alphabet::alphanumeric() }
+        );
+        assert_eq!(
+            parse("decrypt --trithemius --charset printable").unwrap().command,
+            Command::Decrypt { key: Key::Progressive { key: String::from(" "), step: 1 }, tableau: Tableau::Vigenere, alphabet: Alphabet::printable() }
+        );
+        assert_eq!(
+            parse("crack --charset 0123456789ABCDEF -l 4").unwrap().command,
+            Command::CrackAlphabet { length: Some(4), language: Language::English, tableau: Tableau::Vigenere, alphabet: Alphabet::custom("0123456789ABCDEF").unwrap() }
+        );
+        assert_eq!(parse("crack --charset letters").unwrap().command, Command::Crack { length: None, model: None, language: Language::English, ranking: None, tableau: Tableau::Vigenere });
+        assert!(parse("encode --charset AABC -k ABC").is_err());
+        assert!(parse("encode --charset printable --quagmire 1 --alphabet KRYPTOS -k ABC").is_err());
+        assert!(parse("crack --charset printable --autokey").is_err());
+        assert!(parse("crack --charset printable --max-length 9").is_err());
+        assert_eq!(
+            parse("crack --charset russian --lang ru").unwrap().command,
+            Command::CrackAlphabet { length: None, language: Language::Russian, tableau: Tableau::Vigenere, alphabet: Alphabet::russian() }
+        );
+        assert!(parse("crack --lang russian").is_err());
+        assert!(parse("analyse --lang russian").is_err());
+    }
+
+    #[test]
+    fn parses_lang() {
+        assert_eq!(
+            parse("crack --lang german").unwrap().command,
+            Command::Crack { length: None, model: None, language: Language::German, ranking: None, tableau: Tableau::Vigenere }
+        );
+        assert_eq!(
+            parse("crack --autokey --lang=FR").unwrap().command,
+            Command::CrackAutokey { primer_lengths: 1..=20, model: None, language: Language::French, tableau: Tableau::Vigenere }
+        );
+        assert_eq!(parse("analyse --lang it").unwrap().command, Command::Analyse { lengths: 1..=40, language: Language::Italian });
+        assert_eq!(parse("crack --lang auto --cipher beaufort").unwrap().command, Command::CrackAnyLanguage { tableau: Tableau::Beaufort });
+        assert!(parse("crack --lang auto --autokey").is_err());
+        assert!(parse("crack --lang auto -l 5").is_err());
+        assert!(parse("analyse --lang auto").is_err());
+        assert!(parse("crack --lang klingon").is_err());
+        assert!(parse("encode --lang german -k ABC").is_err());
+    }
+
+    #[test]
+    fn parses_drag() {
+        assert_eq!(
+            parse("drag --crib=attack --length 8 --cipher beaufort").unwrap().command,
+            Command::Drag { crib: String::from("attack"), length: 8, position: None, tableau: Tableau::Beaufort }
+        );
+        assert_eq!(
+            parse("drag --crib attack -l 8 --position 74").unwrap().command,
+            Command::Drag { crib: String::from("attack"), length: 8, position: Some(74), tableau: Tableau::Vigenere }
+        );
+        assert!(parse("drag --crib attack").is_err());
+        assert!(parse("drag --length 8").is_err());
+        assert!(parse("drag --crib attack -l 0").is_err());
+        assert!(parse("crack --crib attack").is_err());
+    }
+
+    #[test]
+    fn parses_analyse() {
+        assert_eq!(parse("analyse").unwrap().command, Command::Analyse { lengths: 1..=40, language: Language::English });
+        assert_eq!(parse("analyse --min-length=3 --max-length=9").unwrap().command, Command::Analyse { lengths: 3..=9, language: Language::English });
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse("").is_err());
+        assert!(parse("decrypt").is_err());
+        assert!(parse("crack --length eight").is_err());
+        assert!(parse("analyse --key SECURITY").is_err());
+        assert!(parse("crack --length 8 --candidates 3").is_err());
+        assert!(parse("analyse --min-length 9 --max-length 3").is_err());
+        assert!(parse("analyse --min-length 0").is_err());
+        assert!(parse("analyse --length 3").is_err());
+        assert!(parse("decrypt --key SECURITY --model german.txt").is_err());
+        assert!(parse("encode --key").is_err());
+        assert!(parse("encode --colour red").is_err());
+        assert!(parse("rot13").is_err());
+        assert!(parse("encode --key SECURITY --cipher playfair").is_err());
+        assert!(parse("analyse --cipher beaufort").is_err());
+    }
+
+    #[test]
+    fn parses_help() {
+        assert_eq!(parse("--help").unwrap().command, Command::Help);
+        assert_eq!(parse("crack -h").unwrap().command, Command::Help);
+    }
+}
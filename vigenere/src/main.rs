@@ -0,0 +1,285 @@
+mod args;
+mod report;
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use args::{parse_args, Args, Command, Key, QuagmireKey, USAGE};
+use decode::kasiski;
+use decode_given_key::Ngram;
+use keyphrase::{group_ciphertext, group_ciphertext_in, Alphabet, AutoKey, KeyPhrase, KeyStream, Language, PartialKey, Quagmire, RunningKey, VigenereError};
+
+// anything that can stop a command after its arguments were parsed
+enum CliError {
+    Io(io::Error),
+    Vigenere(VigenereError),
+    NoCribAt(usize), // drag --position where the crib doesn't fit
+}
+
+impl CliError {
+    // exit codes are listed in USAGE, 2 is reserved for command line errors
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Io(_) => 1,
+            CliError::NoCribAt(_) => 6,
+            CliError::Vigenere(VigenereError::InvalidKeyCharacter(_))
+            | CliError::Vigenere(VigenereError::EmptyKey)
+            | CliError::Vigenere(VigenereError::KeyTooShort { .. })
+            | CliError::Vigenere(VigenereError::ZeroKeyLength)
+            | CliError::Vigenere(VigenereError::KeyLengthMismatch { .. }) => 3,
+            CliError::Vigenere(VigenereError::MissingModel(_))
+            | CliError::Vigenere(VigenereError::CorruptModel { .. }) => 4,
+            CliError::Vigenere(VigenereError::CiphertextTooShort { .. }) => 5,
+            CliError::Vigenere(VigenereError::UnsupportedTableau(_))
+            | CliError::Vigenere(VigenereError::InvalidAlphabet(_))
+            | CliError::Vigenere(VigenereError::EmptyCrib) => 7,
+            // only ever raised while parsing --lang
+            CliError::Vigenere(VigenereError::UnknownLanguage(_)) => 2,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "{}", e),
+            CliError::Vigenere(e) => write!(f, "{}", e),
+            CliError::NoCribAt(position) => write!(f, "the crib can't be at position {}", position),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> CliError {
+        CliError::Io(e)
+    }
+}
+
+impl From<VigenereError> for CliError {
+    fn from(e: VigenereError) -> CliError {
+        CliError::Vigenere(e)
+    }
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Command::Help = args.command {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if let Err(e) = run(args) {
+        eprintln!("error: {}", e);
+        process::exit(e.exit_code());
+    }
+}
+
+fn run(args: Args) -> Result<(), CliError> {
+    let text = read_input(args.input.as_deref())?;
+
+    let result = match args.command {
+        Command::Encode { key: Key::Quagmire(key), .. } => {
+            let (quagmire, mut keyphrase) = build_quagmire(key)?;
+            encode::encode_quagmire(&text, &quagmire, &mut keyphrase)?
+        }
+        Command::Encode { key, tableau, alphabet } => encode::encode_in_alphabet(&text, key_stream(key, &alphabet)?.as_mut(), tableau, &alphabet)?,
+        Command::Decrypt { key: Key::Quagmire(key), .. } => {
+            let (quagmire, mut keyphrase) = build_quagmire(key)?;
+            decode_given_key::decode_quagmire(&text, &quagmire, &mut keyphrase)?
+        }
+        Command::Decrypt { key: Key::Partial(key), tableau, .. } => decode_given_key::decode_partial(&text, &PartialKey::new(&key)?, tableau),
+        Command::Decrypt { key, tableau, alphabet } => {
+            decode_given_key::decode_in_alphabet(&text, key_stream(key, &alphabet)?.as_mut(), tableau, &alphabet)?
+        }
+        Command::Crack { length, model, language, ranking, tableau } => {
+            let ngram = load_model(model, language)?;
+            let (key, plaintext) = match (length, ranking) {
+                (Some(length), _) => {
+                    let buckets = group_ciphertext(&text.to_ascii_uppercase(), length);
+                    decode_given_length::decode_given_length_with_model(&text, length, buckets, &ngram, tableau)?
+                }
+                (None, Some(ranking)) => decode::decode_ranked(&text, &ngram, tableau, ranking.lengths, ranking.candidates)?,
+                (None, None) => decode::decode_with_model(&text, &ngram, tableau)?,
+            };
+            format!("{}\n{}", key, plaintext)
+        }
+        Command::CrackPartial { key, model, language, tableau } => {
+            let ngram = load_model(model, language)?;
+            let known = PartialKey::new(&key)?;
+            let buckets = group_ciphertext(&text.to_ascii_uppercase(), known.len());
+            let (key, plaintext) = decode_given_length::decode_given_partial_key(&text, &known, buckets, &ngram, tableau)?;
+            format!("{}\n{}", key, plaintext)
+        }
+        Command::CrackAnnealed { length, model, language, tableau, annealing } => {
+            let ngram = load_model(model, language)?;
+            let solution = decode::anneal::crack_annealed(&text, &ngram, tableau, length, &annealing)?;
+            format!("{}\n{}", solution.key, solution.plaintext)
+        }
+        Command::CrackRestarts { length, model, language, tableau, restarts, seed } => {
+            let ngram = load_model(model, language)?;
+            let solution = decode::restart::crack_with_restarts(&text, &ngram, tableau, length, restarts, seed)?;
+            format!("{}\n\n{}\n{}", report::restarts(&solution), solution.results[0].key, solution.plaintext)
+        }
+        Command::CrackGenetic { length, model, language, tableau, evolution } => {
+            let ngram = load_model(model, language)?;
+            let solution = decode::genetic::crack_genetic(&text, &ngram, tableau, length, &evolution)?;
+            format!("{}\n{}", solution.key, solution.plaintext)
+        }
+        Command::CrackGronsfeld { length, model, language, tableau } => {
+            let ngram = load_model(model, language)?;
+            let (key, plaintext) = match length {
+                Some(length) => {
+                    let buckets = group_ciphertext(&text.to_ascii_uppercase(), length);
+                    decode_given_length::decode_given_length_gronsfeld(&text, length, buckets, &ngram, tableau)?
+                }
+                None => decode::decode_gronsfeld(&text, &ngram, tableau)?,
+            };
+            format!("{}\n{}", key, plaintext)
+        }
+        Command::CrackAutokey { primer_lengths, model, language, tableau } => {
+            let ngram = load_model(model, language)?;
+            let (primer, plaintext) = decode::autokey::crack_autokey(&text, &ngram, tableau, primer_lengths)?;
+            format!("{}\n{}", primer, plaintext)
+        }
+        Command::CrackRunningKey { key_text, model, language, tableau } => {
+            let ngram = load_model(model, language)?;
+            let (offset, plaintext) = decode::running_key::crack_running_key(&text, &read_key_text(&key_text)?, &ngram, tableau)?;
+            format!("{}\n{}", offset, plaintext)
+        }
+        Command::CrackProgressive { length, step, model, language, tableau } => {
+            let ngram = load_model(model, language)?;
+            let solution = decode::progressive::crack_progressive(&text, &ngram, tableau, length, step)?;
+            format!("{}\n{}\n{}", solution.key, solution.step, solution.plaintext)
+        }
+        Command::CrackQuagmire { kind, length, model, language } => {
+            let ngram = load_model(model, language)?;
+            let solution = decode::quagmire::crack_quagmire(&text, &ngram, kind, length)?;
+            format!("{}\n{}\n{}\n{}", solution.key, solution.plain_alphabet, solution.cipher_alphabet, solution.plaintext)
+        }
+        Command::CrackAnyLanguage { tableau } => {
+            let scores = decode::detect::crack_any_language(&text, tableau)?;
+            let best = &scores[0];
+            format!("{}\n\n{}\n{}\n{}", report::languages(&scores), best.language, best.key, best.plaintext)
+        }
+        Command::CrackAlphabet { length, language, tableau, alphabet } => {
+            let (key, plaintext) = match length {
+                Some(length) => {
+                    let buckets = group_ciphertext_in(&text, length, &alphabet);
+                    decode_given_length::decode_given_length_in(&text, length, buckets, tableau, &alphabet, language)?
+                }
+                None => decode::decode_in_alphabet(&text, tableau, &alphabet, language)?,
+            };
+            format!("{}\n{}", key, plaintext)
+        }
+        Command::Analyse { lengths, language } => {
+            let ranked = decode::rank_key_lengths_for(&text, lengths.clone(), language)?;
+            let repeats = kasiski::find_repeats(&text);
+            let periods = kasiski::rank_periods(&repeats, lengths);
+            format!(
+                "{}\n\n{}\n\n{}",
+                report::friedman(decode::estimate_key_length_in(&text, &Alphabet::letters(), language)),
+                report::key_lengths(&ranked),
+                report::kasiski(&repeats, &periods)
+            )
+        }
+        Command::Drag { crib, length, position, tableau } => {
+            let alignments = decode::crib::drag_crib(&text, &crib, length, tableau)?;
+            match position {
+                Some(position) => match alignments.iter().find(|alignment| alignment.position == position) {
+                    Some(alignment) => format!("{}\n{}", alignment.key, alignment.plaintext),
+                    None => return Err(CliError::NoCribAt(position)),
+                },
+                None => report::alignments(&alignments),
+            }
+        }
+        Command::Help => unreachable!(),
+    };
+
+    write_output(args.output.as_deref(), &result)?;
+    Ok(())
+}
+
+// a model from a file is taken to be of --lang, which also sets the letter frequencies and IOC.
+// without one, the model built in for the language, which Russian doesn't have
+fn load_model(model: Option<PathBuf>, language: Language) -> Result<Ngram, VigenereError> {
+    if let Some(path) = model {
+        return Ok(Ngram::from_path(&path)?.with_language(language));
+    }
+    match Ngram::for_language(language) {
+        Some(ngram) => Ok(ngram),
+        None => Err(VigenereError::MissingModel(format!("there is no model for {} built in, give one with --model", language))),
+    }
+}
+
+// the shifts a key over alphabet gives, for a Quagmire key only its periodic key as the alphabets come from build_quagmire
+fn key_stream(key: Key, alphabet: &Alphabet) -> Result<Box<dyn KeyStream>, CliError> {
+    Ok(match key {
+        Key::Repeating(key) => Box::new(KeyPhrase::in_alphabet(&key, alphabet)?),
+        Key::Gronsfeld(digits) => Box::new(KeyPhrase::from_digits(&digits)?),
+        Key::Autokey(primer) => Box::new(AutoKey::in_alphabet(&primer, alphabet)?),
+        Key::Running { text, offset } => Box::new(RunningKey::in_alphabet(&read_key_text(&text)?, offset, alphabet)?),
+        Key::Progressive { key, step } => Box::new(KeyPhrase::in_alphabet(&key, alphabet)?.with_step(step)),
+        Key::Quagmire(key) => Box::new(KeyPhrase::new(key.key)?),
+        Key::Partial(_) => unreachable!("decrypt masks a partial key itself"),
+    })
+}
+
+fn build_quagmire(key: QuagmireKey) -> Result<(Quagmire, KeyPhrase), VigenereError> {
+    let quagmire = Quagmire::new(key.kind, &key.keyword, key.cipher_keyword.as_deref(), key.indicator)?;
+    Ok((quagmire, KeyPhrase::new(key.key)?))
+}
+
+// reads the whole input, dropping the trailing newline left by echo or a text editor
+fn read_input(input: Option<&Path>) -> io::Result<String> {
+    let mut text = match input {
+        Some(path) => fs::read_to_string(path).map_err(|e| with_path(e, path))?,
+        None => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+    };
+    if text.ends_with('\n') {
+        text.pop(); // pop off LF
+        if text.ends_with('\r') {
+            text.pop(); // pop off CR
+        }
+    }
+    Ok(text)
+}
+
+// a running key text from a file, or stdin when the message itself came from --input
+fn read_key_text(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        return Ok(buf);
+    }
+    fs::read_to_string(path).map_err(|e| with_path(e, path))
+}
+
+fn write_output(output: Option<&Path>, result: &str) -> io::Result<()> {
+    match output {
+        Some(path) => fs::write(path, format!("{}\n", result)).map_err(|e| with_path(e, path)),
+        None => {
+            let stdout = io::stdout();
+            let mut handle = stdout.lock();
+            writeln!(handle, "{}", result)
+        }
+    }
+}
+
+// io errors don't say which file they came from
+fn with_path(e: io::Error, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
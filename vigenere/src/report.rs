@@ -0,0 +1,203 @@
+use decode::crib::CribAlignment;
+use decode::detect::LanguageScore;
+use decode::kasiski::{KasiskiPeriod, Repeat};
+use decode::restart::RestartSolution;
+use decode::KeyLengthCandidate;
+
+// how much of each crib alignment's plaintext to show
+const PREVIEW_WIDTH: usize = 60;
+// how many rows of each Kasiski table to print, the rest is mostly chance
+const KASISKI_ROWS: usize = 10;
+// longer repeated sequences are cut short so the table stays readable
+const MAX_SEQUENCE_WIDTH: usize = 12;
+
+// table of key lengths as ranked by decode::rank_key_lengths, best first
+pub fn key_lengths(ranked: &[KeyLengthCandidate]) -> String {
+    let mut out = String::from("Key lengths by index of coincidence:\n");
+    out.push_str("  rank  length  avg IoC  spread  confidence\n");
+    // lengths marked as a multiple are ranked after the rest, see decode::rank_key_lengths
+    for (rank, candidate) in ranked.iter().enumerate() {
+        out.push_str(&format!(
+            "  {:>4}  {:>6}  {:>7.4}  {:>6.4}  {:>9.0}%{}\n",
+            rank + 1,
+            candidate.length,
+            candidate.average_ioc,
+            candidate.ioc_spread,
+            candidate.confidence * 100.0,
+            match candidate.multiple_of {
+                Some(d) => format!("  (multiple of {})", d),
+                None => String::new(),
+            }
+        ));
+    }
+    out.pop(); // trailing LF, write_output adds its own
+    out
+}
+
+// the Friedman test's single figure, printed above the IOC ranking as a sanity check on it
+pub fn friedman(estimate: f64) -> String {
+    format!("Friedman estimate of key length: {:.1}", estimate)
+}
+
+// repeated sequences and the periods they point to, as found by decode::kasiski
+pub fn kasiski(repeats: &[Repeat], periods: &[KasiskiPeriod]) -> String {
+    let mut out = String::from("Kasiski examination:\n");
+    if repeats.is_empty() {
+        out.push_str("  no sequence of 3 or more letters repeats");
+        return out;
+    }
+
+    out.push_str(&format!("  {} repeated sequences, longest first\n", repeats.len()));
+    out.push_str("  sequence      positions                 spacings\n");
+    for repeat in repeats.iter().take(KASISKI_ROWS) {
+        let sequence = if repeat.sequence.len() > MAX_SEQUENCE_WIDTH {
+            format!("{}...", &repeat.sequence[..MAX_SEQUENCE_WIDTH - 3])
+        }
+        else {
+            repeat.sequence.clone()
+        };
+        out.push_str(&format!("  {:<12}  {:<24}  {}\n", sequence, join(&repeat.positions), join(&repeat.spacings)));
+    }
+
+    out.push_str("  rank  period  spacings  score\n");
+    for (rank, period) in periods.iter().take(KASISKI_ROWS).enumerate() {
+        out.push_str(&format!("  {:>4}  {:>6}  {:>8}  {:>5.2}\n", rank + 1, period.length, period.count, period.score));
+    }
+    out.pop(); // trailing LF
+    out
+}
+
+// the key and fitness each language cracked to with --lang auto, best first
+pub fn languages(scores: &[LanguageScore]) -> String {
+    let mut out = String::from("Languages by n-gram fitness:\n");
+    out.push_str("  rank  language    fitness  key\n");
+    for (rank, score) in scores.iter().enumerate() {
+        out.push_str(&format!("  {:>4}  {:<10}  {:>7.3}  {}\n", rank + 1, score.language.name(), score.fitness, score.key));
+    }
+    out.pop(); // trailing LF
+    out
+}
+
+// the keys the hill climbs of decode::restart ended on, best first, and how many got to each
+pub fn restarts(solution: &RestartSolution) -> String {
+    let mut out = format!(
+        "Hill climbs: {} of {} ended on the best key ({:.0}%)\n",
+        solution.results[0].converged,
+        solution.climbs,
+        solution.confidence() * 100.0
+    );
+    out.push_str("  rank  climbs      score  key\n");
+    for (rank, result) in solution.results.iter().enumerate() {
+        out.push_str(&format!("  {:>4}  {:>6}  {:>9.1}  {}\n", rank + 1, result.converged, result.score, result.key));
+    }
+    out.pop(); // trailing LF
+    out
+}
+
+// the crib alignments from decode::crib::drag_crib whose key letters agree, in order of position
+pub fn alignments(alignments: &[CribAlignment]) -> String {
+    let mut out = String::from("Crib positions:\n");
+    let consistent: Vec<&CribAlignment> = alignments.iter().filter(|alignment| alignment.conflicts == 0).collect();
+    if consistent.is_empty() {
+        out.push_str("  the crib's key letters disagree at every position");
+        return out;
+    }
+    let width = usize::max(consistent[0].key.len(), "key".len());
+    out.push_str(&format!("  position  {:<width$}  plaintext\n", "key", width = width));
+    for alignment in consistent {
+        // one line each, however the text was laid out
+        let preview: String = alignment.plaintext.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(PREVIEW_WIDTH).collect();
+        out.push_str(&format!("  {:>8}  {:<width$}  {}\n", alignment.position, alignment.key.to_string(), preview, width = width));
+    }
+    out.pop(); // trailing LF
+    out
+}
+
+fn join(numbers: &[usize]) -> String {
+    numbers.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decode::restart::ClimbResult;
+    use keyphrase::{Language, PartialKey};
+
+    #[test]
+    fn key_length_table() {
+        let ranked = vec![
+            KeyLengthCandidate { length: 8, average_ioc: 0.0731, ioc_spread: 0.0205, confidence: 1.0, multiple_of: None },
+            KeyLengthCandidate { length: 3, average_ioc: 0.0412, ioc_spread: 0.0051, confidence: 0.1, multiple_of: None },
+            KeyLengthCandidate { length: 16, average_ioc: 0.0702, ioc_spread: 0.0311, confidence: 1.0, multiple_of: Some(8) },
+        ];
+        let table = key_lengths(&ranked);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[2], "     1       8   0.0731  0.0205        100%");
+        assert_eq!(lines[3], "     2       3   0.0412  0.0051         10%");
+        assert_eq!(lines[4], "     3      16   0.0702  0.0311        100%  (multiple of 8)");
+    }
+
+    #[test]
+    fn language_table() {
+        let scores = vec![
+            LanguageScore { language: Language::German, fitness: 1.0412, key: String::from("GEHEIM"), plaintext: String::new() },
+            LanguageScore { language: Language::English, fitness: 0.8123, key: String::from("GEHEIM"), plaintext: String::new() },
+        ];
+        let table = languages(&scores);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "     1  german        1.041  GEHEIM");
+        assert_eq!(lines[3], "     2  english       0.812  GEHEIM");
+    }
+
+    #[test]
+    fn restart_table() {
+        let results = vec![
+            ClimbResult { key: String::from("SECURITY"), score: -180.25, converged: 3 },
+            ClimbResult { key: String::from("SECRZUTY"), score: -201.0, converged: 1 },
+        ];
+        let table = restarts(&RestartSolution { results, climbs: 4, plaintext: String::new() });
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Hill climbs: 3 of 4 ended on the best key (75%)");
+        assert_eq!(lines[2], "     1       3     -180.2  SECURITY");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn alignment_table() {
+        let key = |shifts: &[Option<u8>]| PartialKey::from_shifts(shifts.to_vec()).unwrap();
+        let dragged = vec![
+            CribAlignment { position: 3, key: key(&[Some(0), None, Some(2)]), conflicts: 1, plaintext: String::from("a_c") },
+            CribAlignment { position: 4, key: key(&[None, Some(1), Some(2)]), conflicts: 0, plaintext: String::from("_bc\n_bc") },
+        ];
+        let table = alignments(&dragged);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], "         4  ?BC  _bc _bc");
+        assert_eq!(alignments(&dragged[..1]), "Crib positions:\n  the crib's key letters disagree at every position");
+    }
+
+    #[test]
+    fn friedman_line() {
+        assert_eq!(friedman(8.0812), "Friedman estimate of key length: 8.1");
+    }
+
+    #[test]
+    fn kasiski_table() {
+        let repeats = vec![
+            Repeat { sequence: String::from("VVQGYTVVQPHXIN"), positions: vec![0, 72, 144], spacings: vec![72, 72] },
+            Repeat { sequence: String::from("LUR"), positions: vec![11, 159], spacings: vec![148] },
+        ];
+        let periods = vec![KasiskiPeriod { length: 8, count: 2, score: 2.6458 }];
+        let table = kasiski(&repeats, &periods);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[3], "  VVQGYTVVQ...  0,72,144                  72,72");
+        assert_eq!(lines[4], "  LUR           11,159                    148");
+        assert_eq!(lines[6], "     1       8         2   2.65");
+
+        assert_eq!(kasiski(&[], &[]), "Kasiski examination:\n  no sequence of 3 or more letters repeats");
+    }
+}